@@ -0,0 +1,423 @@
+use crate::ray::Ray;
+use crate::tuple::utils::EPSILON;
+use crate::tuple::{Matrix4, Point, Tuple};
+
+/// Compute the distances where a ray enters and leaves the slab
+/// between `min` and `max` along a single axis
+///
+/// This is the building block of every axis-aligned box test, the ray
+/// hits the box only if the three slabs overlap.
+///
+/// # Examples
+/// ```
+/// use ray_tracer::bounds::check_axis;
+///
+/// let (tmin, tmax) = check_axis(-5.0, 1.0, -1.0, 1.0);
+/// assert_eq!((tmin, tmax), (4.0, 6.0));
+/// ```
+pub fn check_axis(origin: f64, direction: f64, min: f64, max: f64) -> (f64, f64) {
+    let tmin_numerator = min - origin;
+    let tmax_numerator = max - origin;
+
+    // A ray parallel to the slab never enters or leaves it
+    let (tmin, tmax) = if direction.abs() >= EPSILON {
+        (tmin_numerator / direction, tmax_numerator / direction)
+    } else {
+        (
+            tmin_numerator * f64::INFINITY,
+            tmax_numerator * f64::INFINITY,
+        )
+    };
+
+    if tmin > tmax {
+        (tmax, tmin)
+    } else {
+        (tmin, tmax)
+    }
+}
+
+/// An axis-aligned bounding box, given by its `min` and `max` corners
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Bounds {
+    pub min: Point,
+    pub max: Point,
+}
+
+impl Bounds {
+    pub fn new(min: Point, max: Point) -> Bounds {
+        Bounds { min, max }
+    }
+
+    /// Create the smallest `Bounds` that contain all of the `points`
+    ///
+    /// # Examples
+    /// ```
+    /// use ray_tracer::bounds::Bounds;
+    /// use ray_tracer::tuple::{Point, Tuple};
+    /// use ray_tracer::point;
+    ///
+    /// let b = Bounds::from_points(&[point!(-3, 7, 2), point!(6, 2, -4)]);
+    /// assert!(b.min == point!(-3, 2, -4));
+    /// assert!(b.max == point!(6, 7, 2));
+    /// ```
+    pub fn from_points(points: &[Point]) -> Bounds {
+        let mut min = Point::new(f64::INFINITY, f64::INFINITY, f64::INFINITY);
+        let mut max = Point::new(f64::NEG_INFINITY, f64::NEG_INFINITY, f64::NEG_INFINITY);
+
+        for p in points {
+            min = Point::new(min.x.min(p.x), min.y.min(p.y), min.z.min(p.z));
+            max = Point::new(max.x.max(p.x), max.y.max(p.y), max.z.max(p.z));
+        }
+
+        Bounds::new(min, max)
+    }
+
+    /// Check if `point` is inside the `Bounds`, borders included
+    ///
+    /// # Examples
+    /// ```
+    /// use ray_tracer::bounds::Bounds;
+    /// use ray_tracer::tuple::{Point, Tuple};
+    /// use ray_tracer::point;
+    ///
+    /// let b = Bounds::new(point!(-1, -1, -1), point!(1, 1, 1));
+    /// assert!(b.contains_point(point!(0.5, 1, 0)));
+    /// assert!(!b.contains_point(point!(2, 0, 0)));
+    /// ```
+    pub fn contains_point(&self, point: Point) -> bool {
+        (self.min.x..=self.max.x).contains(&point.x)
+            && (self.min.y..=self.max.y).contains(&point.y)
+            && (self.min.z..=self.max.z).contains(&point.z)
+    }
+
+    /// Check if `other` is entirely inside the `Bounds`
+    pub fn contains_bounds(&self, other: &Bounds) -> bool {
+        self.contains_point(other.min) && self.contains_point(other.max)
+    }
+
+    /// Check if `ray` goes through the box, using the same slab test
+    /// as a `Cube`
+    ///
+    /// # Examples
+    /// ```
+    /// use ray_tracer::bounds::Bounds;
+    /// use ray_tracer::ray::Ray;
+    /// use ray_tracer::tuple::{Point, Tuple, Vector};
+    /// use ray_tracer::{point, vector};
+    ///
+    /// let b = Bounds::new(point!(-1, -1, -1), point!(1, 1, 1));
+    /// assert!(b.intersects(&Ray::new(point!(5, 0.5, 0), vector!(-1, 0, 0))));
+    /// assert!(!b.intersects(&Ray::new(point!(5, 2, 0), vector!(-1, 0, 0))));
+    /// ```
+    pub fn intersects(&self, ray: &Ray) -> bool {
+        let (o, d) = (ray.origin, ray.direction);
+        let (xtmin, xtmax) = check_axis(o.x, d.x, self.min.x, self.max.x);
+        let (ytmin, ytmax) = check_axis(o.y, d.y, self.min.y, self.max.y);
+        let (ztmin, ztmax) = check_axis(o.z, d.z, self.min.z, self.max.z);
+
+        let tmin = xtmin.max(ytmin).max(ztmin);
+        let tmax = xtmax.min(ytmax).min(ztmax);
+
+        tmin <= tmax
+    }
+
+    /// Cut the `Bounds` in two halves across their largest dimension
+    ///
+    /// # Examples
+    /// ```
+    /// use ray_tracer::bounds::Bounds;
+    /// use ray_tracer::tuple::{Point, Tuple};
+    /// use ray_tracer::point;
+    ///
+    /// let b = Bounds::new(point!(-1, -4, -5), point!(9, 6, 5));
+    /// let (left, right) = b.split();
+    /// assert!(left.max == point!(4, 6, 5));
+    /// assert!(right.min == point!(4, -4, -5));
+    /// ```
+    pub fn split(&self) -> (Bounds, Bounds) {
+        let (min, max) = (self.min, self.max);
+        let (dx, dy, dz) = (max.x - min.x, max.y - min.y, max.z - min.z);
+        let greatest = dx.max(dy).max(dz);
+
+        let (mut x0, mut y0, mut z0) = (min.x, min.y, min.z);
+        let (mut x1, mut y1, mut z1) = (max.x, max.y, max.z);
+        if greatest == dx {
+            x0 += dx / 2.0;
+            x1 = x0;
+        } else if greatest == dy {
+            y0 += dy / 2.0;
+            y1 = y0;
+        } else {
+            z0 += dz / 2.0;
+            z1 = z0;
+        }
+
+        (
+            Bounds::new(min, Point::new(x1, y1, z1)),
+            Bounds::new(Point::new(x0, y0, z0), max),
+        )
+    }
+
+    /// The point halfway between `min` and `max`
+    pub fn center(&self) -> Point {
+        Point::new(
+            (self.min.x + self.max.x) / 2.0,
+            (self.min.y + self.max.y) / 2.0,
+            (self.min.z + self.max.z) / 2.0,
+        )
+    }
+
+    /// The total area of the six faces of the box, zero for empty
+    /// `Bounds`
+    ///
+    /// # Examples
+    /// ```
+    /// use ray_tracer::bounds::Bounds;
+    /// use ray_tracer::tuple::{Point, Tuple};
+    /// use ray_tracer::point;
+    ///
+    /// let b = Bounds::new(point!(0, 0, 0), point!(1, 2, 3));
+    /// assert_eq!(b.surface_area(), 22.0);
+    /// assert_eq!(Bounds::from_points(&[]).surface_area(), 0.0);
+    /// ```
+    pub fn surface_area(&self) -> f64 {
+        let (dx, dy, dz) = (
+            self.max.x - self.min.x,
+            self.max.y - self.min.y,
+            self.max.z - self.min.z,
+        );
+        if dx < 0.0 || dy < 0.0 || dz < 0.0 {
+            return 0.0;
+        }
+
+        2.0 * (dx * dy + dy * dz + dz * dx)
+    }
+
+    /// Check if the `Bounds` contain nothing, like the ones of an empty
+    /// `Group`
+    pub fn is_empty(&self) -> bool {
+        self.min.x > self.max.x || self.min.y > self.max.y || self.min.z > self.max.z
+    }
+
+    /// Check if the `Bounds` enclose a finite volume
+    ///
+    /// Shapes with infinite `Bounds`, like a `Plane`, can't be
+    /// subdivided and should be left out of acceleration structures.
+    pub fn is_finite(&self) -> bool {
+        [self.min, self.max]
+            .iter()
+            .all(|p| p.x.is_finite() && p.y.is_finite() && p.z.is_finite())
+    }
+
+    /// Create the smallest `Bounds` that contain both `self` and `other`
+    pub fn merge(&self, other: &Bounds) -> Bounds {
+        let (a, b) = (self, other);
+        Bounds::new(
+            Point::new(
+                a.min.x.min(b.min.x),
+                a.min.y.min(b.min.y),
+                a.min.z.min(b.min.z),
+            ),
+            Point::new(
+                a.max.x.max(b.max.x),
+                a.max.y.max(b.max.y),
+                a.max.z.max(b.max.z),
+            ),
+        )
+    }
+
+    /// Transform the `Bounds` with `m`, returning the axis-aligned
+    /// `Bounds` that contain the transformed box
+    ///
+    /// Empty `Bounds` stay empty, and infinite `Bounds` stay infinite
+    /// along every axis, since they can't be rotated reliably.
+    pub fn transform(&self, m: &Matrix4) -> Bounds {
+        if self.is_empty() {
+            return *self;
+        }
+        if !self.is_finite() {
+            return Bounds::new(
+                Point::new(f64::NEG_INFINITY, f64::NEG_INFINITY, f64::NEG_INFINITY),
+                Point::new(f64::INFINITY, f64::INFINITY, f64::INFINITY),
+            );
+        }
+
+        let (min, max) = (self.min, self.max);
+        let corners = [
+            min,
+            Point::new(min.x, min.y, max.z),
+            Point::new(min.x, max.y, min.z),
+            Point::new(min.x, max.y, max.z),
+            Point::new(max.x, min.y, min.z),
+            Point::new(max.x, min.y, max.z),
+            Point::new(max.x, max.y, min.z),
+            max,
+        ];
+
+        Bounds::from_points(&corners.map(|corner| *m * corner))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transform::{rotation_x, rotation_y, scaling, translation};
+    use crate::tuple::Vector;
+    use crate::{point, vector};
+    use std::f64::consts::{PI, SQRT_2};
+
+    #[test]
+    fn bounds_contain_bounds() {
+        let b = Bounds::new(point!(5, -2, 0), point!(11, 4, 7));
+        let cases = [
+            (point!(5, -2, 0), point!(11, 4, 7), true),
+            (point!(6, -1, 1), point!(10, 3, 6), true),
+            (point!(4, -3, -1), point!(10, 3, 6), false),
+            (point!(6, -1, 1), point!(12, 5, 8), false),
+        ];
+        for (min, max, expected) in cases {
+            assert_eq!(b.contains_bounds(&Bounds::new(min, max)), expected);
+        }
+    }
+
+    #[test]
+    fn ray_intersects_cubic_bounds() {
+        let b = Bounds::new(point!(-1, -1, -1), point!(1, 1, 1));
+        let cases = [
+            (point!(5, 0.5, 0), vector!(-1, 0, 0), true),
+            (point!(-5, 0.5, 0), vector!(1, 0, 0), true),
+            (point!(0.5, 5, 0), vector!(0, -1, 0), true),
+            (point!(0.5, -5, 0), vector!(0, 1, 0), true),
+            (point!(0.5, 0, 5), vector!(0, 0, -1), true),
+            (point!(0.5, 0, -5), vector!(0, 0, 1), true),
+            (point!(0, 0.5, 0), vector!(0, 0, 1), true),
+            (point!(-2, 0, 0), vector!(2, 4, 6), false),
+            (point!(0, -2, 0), vector!(6, 2, 4), false),
+            (point!(0, 0, -2), vector!(4, 6, 2), false),
+            (point!(2, 0, 2), vector!(0, 0, -1), false),
+            (point!(0, 2, 2), vector!(0, -1, 0), false),
+            (point!(2, 2, 0), vector!(-1, 0, 0), false),
+        ];
+        for (origin, direction, expected) in cases {
+            let r = Ray::new(origin, direction.normalize());
+            assert_eq!(b.intersects(&r), expected);
+        }
+    }
+
+    #[test]
+    fn ray_intersects_noncubic_bounds() {
+        let b = Bounds::new(point!(5, -2, 0), point!(11, 4, 7));
+        let cases = [
+            (point!(15, 1, 2), vector!(-1, 0, 0), true),
+            (point!(-5, -1, 4), vector!(1, 0, 0), true),
+            (point!(7, 6, 5), vector!(0, -1, 0), true),
+            (point!(9, -5, 6), vector!(0, 1, 0), true),
+            (point!(8, 2, 12), vector!(0, 0, -1), true),
+            (point!(6, 0, -5), vector!(0, 0, 1), true),
+            (point!(8, 1, 3.5), vector!(0, 0, 1), true),
+            (point!(9, -1, -8), vector!(2, 4, 6), false),
+            (point!(8, 3, -4), vector!(6, 2, 4), false),
+            (point!(9, -1, -2), vector!(4, 6, 2), false),
+            (point!(4, 0, 9), vector!(0, 0, -1), false),
+            (point!(8, 6, -1), vector!(0, -1, 0), false),
+            (point!(12, 5, 4), vector!(-1, 0, 0), false),
+        ];
+        for (origin, direction, expected) in cases {
+            let r = Ray::new(origin, direction.normalize());
+            assert_eq!(b.intersects(&r), expected);
+        }
+    }
+
+    #[test]
+    fn split_bounds_along_largest_dimension() {
+        let cases = [
+            // A perfect cube is split along x
+            (
+                point!(-1, -4, -5),
+                point!(9, 6, 5),
+                point!(4, 6, 5),
+                point!(4, -4, -5),
+            ),
+            (
+                point!(-1, -2, -3),
+                point!(9, 5.5, 3),
+                point!(4, 5.5, 3),
+                point!(4, -2, -3),
+            ),
+            (
+                point!(-1, -2, -3),
+                point!(5, 8, 3),
+                point!(5, 3, 3),
+                point!(-1, 3, -3),
+            ),
+            (
+                point!(-1, -2, -3),
+                point!(5, 3, 7),
+                point!(5, 3, 2),
+                point!(-1, -2, 2),
+            ),
+        ];
+        for (min, max, left_max, right_min) in cases {
+            let (left, right) = Bounds::new(min, max).split();
+            assert!(left.min == min);
+            assert!(left.max == left_max);
+            assert!(right.min == right_min);
+            assert!(right.max == max);
+        }
+    }
+
+    #[test]
+    fn merge_bounds() {
+        let b1 = Bounds::new(point!(-5, -2, 0), point!(7, 4, 4));
+        let b2 = Bounds::new(point!(8, -7, -2), point!(14, 2, 8));
+        let b = b1.merge(&b2);
+        assert!(b.min == point!(-5, -7, -2));
+        assert!(b.max == point!(14, 4, 8));
+    }
+
+    #[test]
+    fn merge_with_empty_bounds() {
+        let empty = Bounds::from_points(&[]);
+        let b = Bounds::new(point!(-1, -2, -3), point!(1, 2, 3));
+        assert!(empty.merge(&b) == b);
+    }
+
+    #[test]
+    fn transform_bounds() {
+        let b = Bounds::new(point!(-1, -1, -1), point!(1, 1, 1));
+        let m = rotation_x(PI / 4.0) * rotation_y(PI / 4.0);
+        let t = b.transform(&m);
+        assert!((t.min.x + SQRT_2).abs() < 1e-5);
+        assert!((t.min.y + 1.70711).abs() < 1e-5);
+        assert!((t.min.z + 1.70711).abs() < 1e-5);
+        assert!((t.max.x - SQRT_2).abs() < 1e-5);
+        assert!((t.max.y - 1.70711).abs() < 1e-5);
+        assert!((t.max.z - 1.70711).abs() < 1e-5);
+
+        let t = b.transform(&(translation(1.0, 2.0, 3.0) * scaling(2.0, 2.0, 2.0)));
+        assert!(t.min == point!(-1, 0, 1));
+        assert!(t.max == point!(3, 4, 5));
+    }
+
+    #[test]
+    fn transform_empty_bounds() {
+        let empty = Bounds::from_points(&[]);
+        assert!(empty.is_empty());
+        let t = empty.transform(&(rotation_x(PI / 4.0) * scaling(2.0, 2.0, 2.0)));
+        assert!(t.is_empty());
+
+        let b = Bounds::new(point!(-1, -2, -3), point!(1, 2, 3));
+        assert!(t.merge(&b) == b);
+    }
+
+    #[test]
+    fn transform_infinite_bounds() {
+        let b = Bounds::new(
+            point!(f64::NEG_INFINITY, 0, f64::NEG_INFINITY),
+            point!(f64::INFINITY, 0, f64::INFINITY),
+        );
+        let t = b.transform(&rotation_x(PI / 2.0));
+        assert_eq!(t.min.y, f64::NEG_INFINITY);
+        assert_eq!(t.max.z, f64::INFINITY);
+    }
+}
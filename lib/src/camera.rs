@@ -0,0 +1,193 @@
+use crate::canvas::Canvas;
+use crate::ray::Ray;
+use crate::tuple::{Matrix4, Point, Tuple};
+use crate::world::{World, MAX_DEPTH};
+
+/// A camera that maps a `Canvas` one unit in front of the eye
+#[derive(Copy, Clone, Debug)]
+pub struct Camera {
+    hsize: usize,
+    vsize: usize,
+    field_of_view: f64,
+    transform: Matrix4,
+    // Cached so that it is not computed for every pixel
+    inverse: Matrix4,
+    half_width: f64,
+    half_height: f64,
+    pixel_size: f64,
+}
+
+impl Camera {
+    /// Create a `Camera` for a `Canvas` of `hsize` by `vsize` pixels,
+    /// with an angle `field_of_view` in radians
+    pub fn new(hsize: usize, vsize: usize, field_of_view: f64) -> Camera {
+        let half_view = (field_of_view / 2.0).tan();
+        let aspect = hsize as f64 / vsize as f64;
+        let (half_width, half_height) = if aspect >= 1.0 {
+            (half_view, half_view / aspect)
+        } else {
+            (half_view * aspect, half_view)
+        };
+
+        Camera {
+            hsize,
+            vsize,
+            field_of_view,
+            transform: Matrix4::identity(),
+            inverse: Matrix4::identity(),
+            half_width,
+            half_height,
+            pixel_size: half_width * 2.0 / hsize as f64,
+        }
+    }
+
+    /// The width of the rendered `Canvas` in pixels
+    pub fn hsize(&self) -> usize {
+        self.hsize
+    }
+
+    /// The height of the rendered `Canvas` in pixels
+    pub fn vsize(&self) -> usize {
+        self.vsize
+    }
+
+    /// The horizontal angle of view in radians, or the vertical one
+    /// for a canvas taller than it is wide
+    pub fn field_of_view(&self) -> f64 {
+        self.field_of_view
+    }
+
+    pub fn transform(&self) -> &Matrix4 {
+        &self.transform
+    }
+
+    /// Change the transformation of the `Camera`, usually
+    /// created with `view_transform`
+    ///
+    /// # Panics
+    /// If `transform` is not invertible.
+    pub fn set_transform(&mut self, transform: Matrix4) {
+        self.inverse = transform
+            .inverse()
+            .expect("the transformation of a camera must be invertible");
+        self.transform = transform;
+    }
+
+    /// The size in world units of a single pixel on the `Canvas`
+    pub fn pixel_size(&self) -> f64 {
+        self.pixel_size
+    }
+
+    /// Create the `Ray` that starts at the camera and passes through
+    /// the center of the pixel at (`px`, `py`)
+    pub fn ray_for_pixel(&self, px: usize, py: usize) -> Ray {
+        // Offset from the edge of the canvas to the center of the pixel
+        let xoffset = (px as f64 + 0.5) * self.pixel_size;
+        let yoffset = (py as f64 + 0.5) * self.pixel_size;
+
+        // The camera looks toward -z, so +x is to the *left*
+        let world_x = self.half_width - xoffset;
+        let world_y = self.half_height - yoffset;
+
+        let pixel = self.inverse * Point::new(world_x, world_y, -1.0);
+        let origin = self.inverse * Point::origin();
+        let direction = (pixel - origin).normalize();
+
+        Ray::new(origin, direction)
+    }
+
+    /// Render the `world` into a new `Canvas`, one ray per pixel
+    pub fn render(&self, world: &World) -> Canvas {
+        let mut image = Canvas::new(self.hsize, self.vsize);
+
+        for y in 0..self.vsize {
+            for x in 0..self.hsize {
+                let ray = self.ray_for_pixel(x, y);
+                image.write_pixel(x, y, world.color_at(&ray, MAX_DEPTH));
+            }
+        }
+
+        image
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color; // for the macro
+    use crate::color::Color; // for the type
+    use crate::transform::{rotation_y, translation, view_transform};
+    use crate::tuple::utils::float_eq;
+    use crate::tuple::Vector;
+    use crate::world::default_world;
+    use crate::{point, vector};
+    use std::f64::consts::{FRAC_1_SQRT_2, PI};
+
+    #[test]
+    fn construct_camera() {
+        let c = Camera::new(160, 120, PI / 2.0);
+        assert_eq!(c.hsize(), 160);
+        assert_eq!(c.vsize(), 120);
+        assert_eq!(c.field_of_view(), PI / 2.0);
+        assert!(*c.transform() == Matrix4::identity());
+    }
+
+    #[test]
+    fn pixel_size_horizontal_canvas() {
+        let c = Camera::new(200, 125, PI / 2.0);
+        assert!(float_eq(c.pixel_size(), 0.01));
+    }
+
+    #[test]
+    fn pixel_size_vertical_canvas() {
+        let c = Camera::new(125, 200, PI / 2.0);
+        assert!(float_eq(c.pixel_size(), 0.01));
+    }
+
+    #[test]
+    fn ray_through_center_of_canvas() {
+        let c = Camera::new(201, 101, PI / 2.0);
+        let r = c.ray_for_pixel(100, 50);
+        assert!(r.origin == point!(0, 0, 0));
+        assert!(r.direction == vector!(0, 0, -1));
+    }
+
+    #[test]
+    fn ray_through_corner_of_canvas() {
+        let c = Camera::new(201, 101, PI / 2.0);
+        let r = c.ray_for_pixel(0, 0);
+        assert!(r.origin == point!(0, 0, 0));
+        assert!((r.direction.x - 0.66519).abs() < 1e-5);
+        assert!((r.direction.y - 0.33259).abs() < 1e-5);
+        assert!((r.direction.z + 0.66851).abs() < 1e-5);
+    }
+
+    #[test]
+    fn ray_when_camera_is_transformed() {
+        let mut c = Camera::new(201, 101, PI / 2.0);
+        c.set_transform(rotation_y(PI / 4.0) * translation(0.0, -2.0, 5.0));
+        let r = c.ray_for_pixel(100, 50);
+        assert!(r.origin == point!(0, 2, -5));
+        assert!(r.direction == vector!(FRAC_1_SQRT_2, 0, -FRAC_1_SQRT_2));
+    }
+
+    #[test]
+    fn render_world() {
+        let w = default_world();
+        let mut c = Camera::new(11, 11, PI / 2.0);
+        let from = point!(0, 0, -5);
+        let to = point!(0, 0, 0);
+        let up = vector!(0, 1, 0);
+        c.set_transform(view_transform(from, to, up));
+
+        let image = c.render(&w);
+        assert_eq!(image.width, 11);
+        assert_eq!(image.height, 11);
+        let pixel = image.pixel_at(5, 5);
+        assert!((pixel.red - 0.38066).abs() < 1e-5);
+        assert!((pixel.green - 0.47583).abs() < 1e-5);
+        assert!((pixel.blue - 0.2855).abs() < 1e-5);
+        // The corners miss both spheres
+        assert!(image.pixel_at(0, 0) == color!(0, 0, 0));
+    }
+}
@@ -1,7 +1,94 @@
 use crate::color; // for the macro
 use crate::color::Color; // for the type
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
 
 const MAX_COLOR_VALUE: usize = 255;
+const MAX_LINE_LENGTH: usize = 70;
+
+/// The errors that make a PPM image impossible to read
+#[derive(Debug)]
+pub enum PpmError {
+    Io(io::Error),
+    /// The image does not start with `P3` or `P6`
+    UnsupportedFormat(String),
+    /// A header field or a color value that is not a valid number
+    InvalidValue(String),
+    /// The image ends before every pixel is read
+    MissingData,
+}
+
+impl fmt::Display for PpmError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PpmError::Io(err) => write!(f, "could not read the image: {}", err),
+            PpmError::UnsupportedFormat(magic) => {
+                write!(f, "unsupported image format `{}`", magic)
+            }
+            PpmError::InvalidValue(value) => write!(f, "invalid value `{}`", value),
+            PpmError::MissingData => write!(f, "the image ends before its last pixel"),
+        }
+    }
+}
+
+impl Error for PpmError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            PpmError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for PpmError {
+    fn from(err: io::Error) -> Self {
+        PpmError::Io(err)
+    }
+}
+
+/// Read the whitespace separated tokens of a PPM image, skipping the
+/// comments that start with `#`
+struct PpmTokens<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> PpmTokens<'a> {
+    fn next_token(&mut self) -> Option<&'a [u8]> {
+        loop {
+            match self.bytes.get(self.position)? {
+                b'#' => {
+                    while self.bytes.get(self.position).is_some_and(|&b| b != b'\n') {
+                        self.position += 1;
+                    }
+                }
+                b if b.is_ascii_whitespace() => self.position += 1,
+                _ => break,
+            }
+        }
+
+        let start = self.position;
+        while self
+            .bytes
+            .get(self.position)
+            .is_some_and(|b| !b.is_ascii_whitespace())
+        {
+            self.position += 1;
+        }
+
+        Some(&self.bytes[start..self.position])
+    }
+
+    fn next_number(&mut self) -> Result<usize, PpmError> {
+        let token = self.next_token().ok_or(PpmError::MissingData)?;
+        let text = String::from_utf8_lossy(token);
+        text.parse()
+            .map_err(|_| PpmError::InvalidValue(text.into_owned()))
+    }
+}
 
 #[derive(Clone, Debug)]
 pub struct Canvas {
@@ -13,17 +100,14 @@ pub struct Canvas {
 fn scale_color(c: f64) -> usize {
     let float_max_value = MAX_COLOR_VALUE as f64;
 
-    ((c * float_max_value)
-        .floor()
-        .min(float_max_value as f64)
-        .max(0.0)) as usize
+    ((c * float_max_value).floor().min(float_max_value).max(0.0)) as usize
 }
 
 impl Canvas {
     pub fn new(width: usize, height: usize) -> Canvas {
         Canvas {
-            width: width,
-            height: height,
+            width,
+            height,
             // Fill the data with black "pixels"
             data: vec![vec![color!(0.0, 0.0, 0.0); width]; height],
         }
@@ -60,11 +144,89 @@ impl Canvas {
     /// assert!(canvas1.pixel_at(1, 1) == new_color);
     /// ```
     pub fn write_pixel(&mut self, w: usize, h: usize, c: Color) {
-        if h <= self.height && w <= self.width {
+        if h < self.height && w < self.width {
             self.data[h][w] = c;
         }
     }
 
+    /// Read a `Canvas` from the contents of a PPM image, either in the
+    /// plain `P3` format or the binary `P6` one
+    ///
+    /// # Examples
+    /// ```
+    /// use ray_tracer::canvas::Canvas;
+    /// use ray_tracer::color::Color;
+    /// use ray_tracer::color;
+    ///
+    /// let ppm = "P3\n2 1\n255\n255 0 0  0 0 255\n";
+    /// let canvas = Canvas::from_ppm(ppm.as_bytes()).unwrap();
+    /// assert!(canvas.pixel_at(1, 0) == color!(0, 0, 1));
+    /// ```
+    pub fn from_ppm(bytes: &[u8]) -> Result<Canvas, PpmError> {
+        let mut tokens = PpmTokens { bytes, position: 0 };
+        let magic = tokens.next_token().ok_or(PpmError::MissingData)?;
+        let binary = match magic {
+            b"P3" => false,
+            b"P6" => true,
+            _ => {
+                let magic = String::from_utf8_lossy(magic).into_owned();
+                return Err(PpmError::UnsupportedFormat(magic));
+            }
+        };
+        let width = tokens.next_number()?;
+        let height = tokens.next_number()?;
+        let max_value = tokens.next_number()?;
+        if max_value == 0 || max_value > 65535 {
+            return Err(PpmError::InvalidValue(max_value.to_string()));
+        }
+        if width == 0 || height == 0 {
+            return Err(PpmError::InvalidValue(format!("{} {}", width, height)));
+        }
+
+        let sample_count = width
+            .checked_mul(height)
+            .and_then(|pixels| pixels.checked_mul(3))
+            .ok_or_else(|| PpmError::InvalidValue(format!("{} {}", width, height)))?;
+
+        // Every sample takes at least a byte, so a header that claims
+        // more than the input holds can't cause a huge allocation
+        let mut samples = Vec::with_capacity(sample_count.min(bytes.len()));
+        if binary {
+            // A single whitespace separates the header from the data,
+            // where samples take two bytes if they don't fit in one
+            let data = bytes.get(tokens.position + 1..).unwrap_or_default();
+            let sample_size = if max_value < 256 { 1 } else { 2 };
+            for chunk in data.chunks_exact(sample_size).take(sample_count) {
+                let value = chunk.iter().fold(0, |acc, &b| (acc << 8) | b as usize);
+                samples.push(value);
+            }
+        } else {
+            for _ in 0..sample_count {
+                samples.push(tokens.next_number()?);
+            }
+        }
+        if samples.len() < sample_count {
+            return Err(PpmError::MissingData);
+        }
+        if let Some(value) = samples.iter().find(|&&value| value > max_value) {
+            return Err(PpmError::InvalidValue(value.to_string()));
+        }
+
+        let scale = |value: usize| value as f64 / max_value as f64;
+        let mut canvas = Canvas::new(width, height);
+        for (i, rgb) in samples.chunks_exact(3).enumerate() {
+            let c = color!(scale(rgb[0]), scale(rgb[1]), scale(rgb[2]));
+            canvas.write_pixel(i % width, i / width, c);
+        }
+
+        Ok(canvas)
+    }
+
+    /// Read a `Canvas` from a PPM image on disk, see `Canvas::from_ppm`
+    pub fn load_ppm<P: AsRef<Path>>(path: P) -> Result<Canvas, PpmError> {
+        Canvas::from_ppm(&fs::read(path)?)
+    }
+
     pub fn canvas_to_ppm(&self) -> String {
         let mut ppm_string = String::new();
         // Create the first three lines, the header
@@ -75,17 +237,33 @@ impl Canvas {
         // Add the maximum value that the colors can take
         ppm_string.push_str(format!("{}\n", MAX_COLOR_VALUE).as_str());
 
-        // TODO: Make it so that it can print the three channels column-wise
-        for col in 0..self.width {
-            for row in 0..self.height {
+        // Every row of the canvas is a line, wrapped so that no line
+        // is longer than `MAX_LINE_LENGTH` characters
+        for row in 0..self.height {
+            let mut line = String::new();
+            for col in 0..self.width {
                 let current_color = self.pixel_at(col, row);
-                let new_red = scale_color(current_color.red);
-                let new_green = scale_color(current_color.green);
-                let new_blue = scale_color(current_color.blue);
-                // Write the colors to the PPM string we have so far
-                ppm_string.push_str(format!("{} {} {}\n", new_red, new_green, new_blue).as_str());
-                println!("{}", ppm_string);
+                let channels = [
+                    scale_color(current_color.red),
+                    scale_color(current_color.green),
+                    scale_color(current_color.blue),
+                ];
+                for value in channels {
+                    let value = value.to_string();
+                    if !line.is_empty() && line.len() + value.len() + 1 > MAX_LINE_LENGTH {
+                        ppm_string.push_str(line.as_str());
+                        ppm_string.push('\n');
+                        line.clear();
+                    }
+                    if !line.is_empty() {
+                        line.push(' ');
+                    }
+                    line.push_str(value.as_str());
+                }
             }
+            // Write the colors to the PPM string we have so far
+            ppm_string.push_str(line.as_str());
+            ppm_string.push('\n');
         }
 
         ppm_string
@@ -153,4 +331,93 @@ mod tests {
         assert_eq!(lines.next().unwrap(), "0 0 0 0 0 0 0 127 0 0 0 0 0 0 0");
         assert_eq!(lines.next().unwrap(), "0 0 0 0 0 0 0 0 0 0 0 0 0 0 255");
     }
+
+    #[test]
+    fn read_plain_ppm() {
+        let ppm = "P3\n4 3\n255\n\
+                   255 127 0  0 127 255  127 255 0  255 255 255\n\
+                   0 0 0  255 0 0  0 255 0  0 0 255\n\
+                   255 255 0  0 255 255  255 0 255  127 127 127\n";
+        let c = Canvas::from_ppm(ppm.as_bytes()).unwrap();
+        assert_eq!(c.width, 4);
+        assert_eq!(c.height, 3);
+        assert!(c.pixel_at(0, 0) == color!(1, 127.0 / 255.0, 0));
+        assert!(c.pixel_at(3, 0) == color!(1, 1, 1));
+        assert!(c.pixel_at(1, 1) == color!(1, 0, 0));
+        assert!(c.pixel_at(3, 2) == color!(127.0 / 255.0, 127.0 / 255.0, 127.0 / 255.0));
+    }
+
+    #[test]
+    fn read_ppm_with_comments_and_any_line_length() {
+        let ppm = "P3\n# a comment\n1 2\n# another one\n100\n100\n50\n25 0 0\n100\n";
+        let c = Canvas::from_ppm(ppm.as_bytes()).unwrap();
+        assert!(c.pixel_at(0, 0) == color!(1, 0.5, 0.25));
+        assert!(c.pixel_at(0, 1) == color!(0, 0, 1));
+    }
+
+    #[test]
+    fn read_binary_ppm() {
+        let mut ppm = b"P6\n2 1\n255\n".to_vec();
+        ppm.extend_from_slice(&[255, 0, 0, 0, 51, 255]);
+        let c = Canvas::from_ppm(&ppm).unwrap();
+        assert!(c.pixel_at(0, 0) == color!(1, 0, 0));
+        assert!(c.pixel_at(1, 0) == color!(0, 0.2, 1));
+
+        let mut ppm = b"P6 1 1 65535 ".to_vec();
+        ppm.extend_from_slice(&[255, 255, 0, 0, 128, 0]);
+        let c = Canvas::from_ppm(&ppm).unwrap();
+        assert!(c.pixel_at(0, 0) == color!(1, 0, 32768.0 / 65535.0));
+    }
+
+    #[test]
+    fn written_ppm_can_be_read_back() {
+        let mut c = Canvas::new(30, 4);
+        c.write_pixel(29, 3, color!(1, 0, 1));
+        let read = Canvas::from_ppm(c.canvas_to_ppm().as_bytes()).unwrap();
+        assert_eq!(read.width, 30);
+        assert!(read.pixel_at(29, 3) == color!(1, 0, 1));
+        assert!(read.pixel_at(0, 0) == color!(0, 0, 0));
+    }
+
+    #[test]
+    fn reject_invalid_ppm() {
+        let err = Canvas::from_ppm(b"P32\n1 1\n255\n0 0 0\n").unwrap_err();
+        assert!(matches!(err, PpmError::UnsupportedFormat(ref m) if m == "P32"));
+        let err = Canvas::from_ppm(b"P3\n1 x\n255\n0 0 0\n").unwrap_err();
+        assert_eq!(err.to_string(), "invalid value `x`");
+        let err = Canvas::from_ppm(b"P3\n2 1\n255\n0 0 0\n").unwrap_err();
+        assert!(matches!(err, PpmError::MissingData));
+        let err = Canvas::load_ppm("/nonexistent/image.ppm").unwrap_err();
+        assert!(matches!(err, PpmError::Io(_)));
+    }
+
+    #[test]
+    fn reject_ppm_too_large_to_count() {
+        let err = Canvas::from_ppm(b"P3\n4294967296 4294967296 255\n0 0 0\n").unwrap_err();
+        assert_eq!(err.to_string(), "invalid value `4294967296 4294967296`");
+    }
+
+    #[test]
+    fn huge_ppm_header_without_data() {
+        let err = Canvas::from_ppm(b"P6\n100000 100000 255\n").unwrap_err();
+        assert!(matches!(err, PpmError::MissingData));
+        let err = Canvas::from_ppm(b"P3\n100000 100000 255\n1 2 3\n").unwrap_err();
+        assert!(matches!(err, PpmError::MissingData));
+    }
+
+    #[test]
+    fn reject_empty_ppm() {
+        let err = Canvas::from_ppm(b"P3\n0 4294967295 255\n").unwrap_err();
+        assert_eq!(err.to_string(), "invalid value `0 4294967295`");
+        let err = Canvas::from_ppm(b"P6\n3 0 255\n").unwrap_err();
+        assert_eq!(err.to_string(), "invalid value `3 0`");
+    }
+
+    #[test]
+    fn reject_samples_above_max_value() {
+        let err = Canvas::from_ppm(b"P3\n1 1\n255\n0 256 0\n").unwrap_err();
+        assert_eq!(err.to_string(), "invalid value `256`");
+        let err = Canvas::from_ppm(b"P6\n1 1\n100\n\x00\x65\x00").unwrap_err();
+        assert_eq!(err.to_string(), "invalid value `101`");
+    }
 }
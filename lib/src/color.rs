@@ -74,11 +74,7 @@ impl PartialEq for Color {
         let ytrue = float_eq(self.blue, other.blue);
         let ztrue = float_eq(self.green, other.green);
 
-        if xtrue && ytrue && ztrue {
-            true
-        } else {
-            false
-        }
+        xtrue && ytrue && ztrue
     }
 }
 
@@ -0,0 +1,415 @@
+use crate::ray::Ray;
+use crate::shapes::Shape;
+use crate::tuple::utils::EPSILON;
+use crate::tuple::{Point, Vector};
+use std::ops::Deref;
+
+/// The distance `t` along a `Ray` where it hits an `object`
+///
+/// Triangles also record where on their surface they were hit, with
+/// the barycentric coordinates `u` and `v`.
+#[derive(Copy, Clone, Debug)]
+pub struct Intersection<'a> {
+    pub t: f64,
+    pub object: &'a dyn Shape,
+    pub u: f64,
+    pub v: f64,
+}
+
+impl<'a> Intersection<'a> {
+    pub fn new(t: f64, object: &'a dyn Shape) -> Intersection<'a> {
+        Intersection::with_uv(t, object, 0.0, 0.0)
+    }
+
+    /// Create an `Intersection` that also records the `u` and `v`
+    /// coordinates of the hit
+    pub fn with_uv(t: f64, object: &'a dyn Shape, u: f64, v: f64) -> Intersection<'a> {
+        Intersection { t, object, u, v }
+    }
+
+    /// Precompute the state of the hit that is needed for shading
+    ///
+    /// `xs` are all the intersections along `ray`, including the hit
+    /// itself, they tell which objects the hit is inside of and so
+    /// the refractive indices on each side of the surface.
+    pub fn prepare_computations(&self, ray: &Ray, xs: &[Intersection<'a>]) -> Computations<'a> {
+        let point = ray.position(self.t);
+        let eyev = -ray.direction;
+        let mut normalv = self.object.normal_at(point, self);
+
+        // The eye is inside the shape if the normal points away from it
+        let inside = normalv.dot(&eyev) < 0.0;
+        if inside {
+            normalv = -normalv;
+        }
+        let reflectv = ray.direction.reflect(&normalv);
+        let (n1, n2) = self.refractive_indices(xs);
+
+        Computations {
+            t: self.t,
+            object: self.object,
+            point,
+            eyev,
+            normalv,
+            inside,
+            reflectv,
+            // Slightly above the surface, to avoid self intersections
+            over_point: point + normalv * EPSILON,
+            // Slightly below the surface, where refracted rays start
+            under_point: point - normalv * EPSILON,
+            n1,
+            n2,
+        }
+    }
+
+    /// Find the refractive indices of the materials the `Ray` leaves
+    /// and enters at this hit
+    ///
+    /// Walking through `xs` in order, every intersection enters or
+    /// leaves an object; the innermost object that contains the hit on
+    /// each side gives the index, or a vacuum if there is none.
+    fn refractive_indices(&self, xs: &[Intersection<'a>]) -> (f64, f64) {
+        let index_of = |containers: &[&dyn Shape]| {
+            containers
+                .last()
+                .map_or(1.0, |object| object.material().refractive_index)
+        };
+        let mut containers: Vec<&dyn Shape> = Vec::new();
+        let mut n1 = 1.0;
+
+        for i in xs {
+            if i == self {
+                n1 = index_of(&containers);
+            }
+
+            match containers
+                .iter()
+                .position(|object| std::ptr::addr_eq(*object, i.object))
+            {
+                Some(position) => {
+                    containers.remove(position);
+                }
+                None => containers.push(i.object),
+            }
+
+            if i == self {
+                return (n1, index_of(&containers));
+            }
+        }
+
+        (n1, index_of(&containers))
+    }
+}
+
+impl PartialEq for Intersection<'_> {
+    /// Two `Intersection`s are equal if they happen at the same `t`
+    /// on the very same object
+    fn eq(&self, other: &Self) -> bool {
+        self.t == other.t && std::ptr::addr_eq(self.object, other.object)
+    }
+}
+
+/// The state of a hit, precomputed once so it can be reused when shading
+#[derive(Copy, Clone, Debug)]
+pub struct Computations<'a> {
+    pub t: f64,
+    pub object: &'a dyn Shape,
+    pub point: Point,
+    pub eyev: Vector,
+    pub normalv: Vector,
+    pub inside: bool,
+    /// The direction of the `Ray` after it bounces off the surface
+    pub reflectv: Vector,
+    pub over_point: Point,
+    pub under_point: Point,
+    /// The refractive index of the material the `Ray` comes from
+    pub n1: f64,
+    /// The refractive index of the material the `Ray` goes into
+    pub n2: f64,
+}
+
+impl Computations<'_> {
+    /// Approximate the fraction of the light that is reflected rather
+    /// than refracted at the hit, with Schlick's formula
+    ///
+    /// The fraction grows at grazing angles, and is 1.0 under total
+    /// internal reflection.
+    pub fn schlick(&self) -> f64 {
+        let mut cos = self.eyev.dot(&self.normalv);
+
+        // Total internal reflection only happens when n1 > n2
+        if self.n1 > self.n2 {
+            let n = self.n1 / self.n2;
+            let sin2_t = n.powi(2) * (1.0 - cos.powi(2));
+            if sin2_t > 1.0 {
+                return 1.0;
+            }
+            // Use the angle of the refracted ray instead
+            cos = (1.0 - sin2_t).sqrt();
+        }
+
+        let r0 = ((self.n1 - self.n2) / (self.n1 + self.n2)).powi(2);
+        r0 + (1.0 - r0) * (1.0 - cos).powi(5)
+    }
+}
+
+/// A collection of `Intersection`s, always sorted by increasing `t`
+#[derive(Clone, Debug, Default)]
+pub struct Intersections<'a> {
+    data: Vec<Intersection<'a>>,
+}
+
+impl<'a> Intersections<'a> {
+    pub fn new(mut data: Vec<Intersection<'a>>) -> Intersections<'a> {
+        data.sort_by(|a, b| a.t.total_cmp(&b.t));
+        Intersections { data }
+    }
+
+    /// Return the visible `Intersection`, which is the one with the
+    /// lowest non-negative `t`
+    ///
+    /// # Examples
+    /// ```
+    /// use ray_tracer::intersection::{Intersection, Intersections};
+    /// use ray_tracer::shapes::Sphere;
+    ///
+    /// let s = Sphere::new();
+    /// let xs = Intersections::new(vec![Intersection::new(2.0, &s), Intersection::new(-1.0, &s)]);
+    /// assert_eq!(xs.hit().unwrap().t, 2.0);
+    /// ```
+    pub fn hit(&self) -> Option<&Intersection<'a>> {
+        self.data.iter().find(|i| i.t >= 0.0)
+    }
+}
+
+impl<'a> Deref for Intersections<'a> {
+    type Target = [Intersection<'a>];
+
+    fn deref(&self) -> &Self::Target {
+        &self.data
+    }
+}
+
+impl<'a> IntoIterator for Intersections<'a> {
+    type Item = Intersection<'a>;
+    type IntoIter = std::vec::IntoIter<Intersection<'a>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.data.into_iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::material::{glass, Material};
+    use crate::shapes::{Plane, Sphere};
+    use crate::transform::{scaling, translation};
+    use crate::tuple::Tuple;
+    use crate::{point, vector};
+    use std::f64::consts::{FRAC_1_SQRT_2, SQRT_2};
+
+    #[test]
+    fn create_intersection() {
+        let s = Sphere::new();
+        let i = Intersection::new(3.5, &s);
+        assert_eq!(i.t, 3.5);
+        assert!(std::ptr::addr_eq(i.object, &s));
+    }
+
+    #[test]
+    fn aggregate_intersections() {
+        let s = Sphere::new();
+        let i1 = Intersection::new(1.0, &s);
+        let i2 = Intersection::new(2.0, &s);
+        let xs = Intersections::new(vec![i2, i1]);
+        assert_eq!(xs.len(), 2);
+        assert_eq!(xs[0].t, 1.0);
+        assert_eq!(xs[1].t, 2.0);
+    }
+
+    #[test]
+    fn hit_all_positive() {
+        let s = Sphere::new();
+        let i1 = Intersection::new(1.0, &s);
+        let i2 = Intersection::new(2.0, &s);
+        let xs = Intersections::new(vec![i2, i1]);
+        assert!(xs.hit() == Some(&i1));
+    }
+
+    #[test]
+    fn hit_some_negative() {
+        let s = Sphere::new();
+        let i1 = Intersection::new(-1.0, &s);
+        let i2 = Intersection::new(1.0, &s);
+        let xs = Intersections::new(vec![i2, i1]);
+        assert!(xs.hit() == Some(&i2));
+    }
+
+    #[test]
+    fn hit_all_negative() {
+        let s = Sphere::new();
+        let i1 = Intersection::new(-2.0, &s);
+        let i2 = Intersection::new(-1.0, &s);
+        let xs = Intersections::new(vec![i2, i1]);
+        assert!(xs.hit().is_none());
+    }
+
+    #[test]
+    fn hit_is_lowest_non_negative() {
+        let s = Sphere::new();
+        let i1 = Intersection::new(5.0, &s);
+        let i2 = Intersection::new(7.0, &s);
+        let i3 = Intersection::new(-3.0, &s);
+        let i4 = Intersection::new(2.0, &s);
+        let xs = Intersections::new(vec![i1, i2, i3, i4]);
+        assert!(xs.hit() == Some(&i4));
+    }
+
+    #[test]
+    fn precompute_state_of_intersection() {
+        let r = Ray::new(point!(0, 0, -5), vector!(0, 0, 1));
+        let shape = Sphere::new();
+        let i = Intersection::new(4.0, &shape);
+        let comps = i.prepare_computations(&r, &[i]);
+        assert_eq!(comps.t, i.t);
+        assert!(std::ptr::addr_eq(comps.object, &shape));
+        assert!(comps.point == point!(0, 0, -1));
+        assert!(comps.eyev == vector!(0, 0, -1));
+        assert!(comps.normalv == vector!(0, 0, -1));
+    }
+
+    #[test]
+    fn hit_on_the_outside() {
+        let r = Ray::new(point!(0, 0, -5), vector!(0, 0, 1));
+        let shape = Sphere::new();
+        let i = Intersection::new(4.0, &shape);
+        let comps = i.prepare_computations(&r, &[i]);
+        assert!(!comps.inside);
+    }
+
+    #[test]
+    fn hit_on_the_inside() {
+        let r = Ray::new(point!(0, 0, 0), vector!(0, 0, 1));
+        let shape = Sphere::new();
+        let i = Intersection::new(1.0, &shape);
+        let comps = i.prepare_computations(&r, &[i]);
+        assert!(comps.point == point!(0, 0, 1));
+        assert!(comps.eyev == vector!(0, 0, -1));
+        assert!(comps.inside);
+        // The normal is inverted so that it points towards the eye
+        assert!(comps.normalv == vector!(0, 0, -1));
+    }
+
+    #[test]
+    fn precompute_reflection_vector() {
+        let shape = Plane::new();
+        let r = Ray::new(point!(0, 1, -1), vector!(0, -FRAC_1_SQRT_2, FRAC_1_SQRT_2));
+        let i = Intersection::new(SQRT_2, &shape);
+        let comps = i.prepare_computations(&r, &[i]);
+        assert!(comps.reflectv == vector!(0, FRAC_1_SQRT_2, FRAC_1_SQRT_2));
+    }
+
+    #[test]
+    fn hit_offsets_the_point() {
+        let r = Ray::new(point!(0, 0, -5), vector!(0, 0, 1));
+        let mut shape = Sphere::new();
+        shape.set_transform(translation(0.0, 0.0, 1.0));
+        let i = Intersection::new(5.0, &shape);
+        let comps = i.prepare_computations(&r, &[i]);
+        assert!(comps.over_point.z < -EPSILON / 2.0);
+        assert!(comps.point.z > comps.over_point.z);
+    }
+
+    #[test]
+    fn find_n1_and_n2_at_various_intersections() {
+        let mut a = Sphere::new();
+        a.set_transform(scaling(2.0, 2.0, 2.0));
+        a.set_material(glass());
+        let mut b = Sphere::new();
+        b.set_transform(translation(0.0, 0.0, -0.25));
+        b.set_material(Material {
+            refractive_index: 2.0,
+            ..glass()
+        });
+        let mut c = Sphere::new();
+        c.set_transform(translation(0.0, 0.0, 0.25));
+        c.set_material(Material {
+            refractive_index: 2.5,
+            ..glass()
+        });
+
+        let r = Ray::new(point!(0, 0, -4), vector!(0, 0, 1));
+        let xs = Intersections::new(vec![
+            Intersection::new(2.0, &a),
+            Intersection::new(2.75, &b),
+            Intersection::new(3.25, &c),
+            Intersection::new(4.75, &b),
+            Intersection::new(5.25, &c),
+            Intersection::new(6.0, &a),
+        ]);
+        let expected = [
+            (1.0, 1.5),
+            (1.5, 2.0),
+            (2.0, 2.5),
+            (2.5, 2.5),
+            (2.5, 1.5),
+            (1.5, 1.0),
+        ];
+        for (i, (n1, n2)) in xs.iter().zip(expected) {
+            let comps = i.prepare_computations(&r, &xs);
+            assert_eq!((comps.n1, comps.n2), (n1, n2));
+        }
+    }
+
+    #[test]
+    fn hit_offsets_the_under_point() {
+        let r = Ray::new(point!(0, 0, -5), vector!(0, 0, 1));
+        let mut shape = Sphere::new();
+        shape.set_material(glass());
+        shape.set_transform(translation(0.0, 0.0, 1.0));
+        let i = Intersection::new(5.0, &shape);
+        let comps = i.prepare_computations(&r, &[i]);
+        assert!(comps.under_point.z > EPSILON / 2.0);
+        assert!(comps.point.z < comps.under_point.z);
+    }
+
+    fn glass_sphere() -> Sphere {
+        let mut shape = Sphere::new();
+        shape.set_material(glass());
+        shape
+    }
+
+    #[test]
+    fn schlick_under_total_internal_reflection() {
+        let shape = glass_sphere();
+        let r = Ray::new(point!(0, 0, FRAC_1_SQRT_2), vector!(0, 1, 0));
+        let xs = [
+            Intersection::new(-FRAC_1_SQRT_2, &shape),
+            Intersection::new(FRAC_1_SQRT_2, &shape),
+        ];
+        let comps = xs[1].prepare_computations(&r, &xs);
+        assert_eq!(comps.schlick(), 1.0);
+    }
+
+    #[test]
+    fn schlick_with_perpendicular_viewing_angle() {
+        let shape = glass_sphere();
+        let r = Ray::new(point!(0, 0, 0), vector!(0, 1, 0));
+        let xs = [
+            Intersection::new(-1.0, &shape),
+            Intersection::new(1.0, &shape),
+        ];
+        let comps = xs[1].prepare_computations(&r, &xs);
+        assert!((comps.schlick() - 0.04).abs() < 1e-5);
+    }
+
+    #[test]
+    fn schlick_with_small_angle_and_n2_greater_than_n1() {
+        let shape = glass_sphere();
+        let r = Ray::new(point!(0, 0.99, -2), vector!(0, 0, 1));
+        let xs = [Intersection::new(1.8589, &shape)];
+        let comps = xs[0].prepare_computations(&r, &xs);
+        assert!((comps.schlick() - 0.48873).abs() < 1e-5);
+    }
+}
@@ -1,4 +1,16 @@
 // Exports
+pub mod bounds;
+pub mod camera;
 pub mod canvas;
 pub mod color;
+pub mod intersection;
+pub mod light;
+pub mod material;
+pub mod noise;
+pub mod obj_file;
+pub mod patterns;
+pub mod ray;
+pub mod shapes;
+pub mod transform;
 pub mod tuple;
+pub mod world;
@@ -0,0 +1,193 @@
+use crate::color; // for the macro
+use crate::color::Color; // for the type
+use crate::material::Material;
+use crate::shapes::Shape;
+use crate::tuple::{Point, Vector};
+
+/// A light source with no size, that shines equally in all directions
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct PointLight {
+    pub position: Point,
+    pub intensity: Color,
+}
+
+impl PointLight {
+    pub fn new(position: Point, intensity: Color) -> PointLight {
+        PointLight {
+            position,
+            intensity,
+        }
+    }
+}
+
+/// Compute the `Color` of a `point` using the Phong reflection model
+///
+/// The result is the sum of the ambient, diffuse and specular
+/// contributions, where `eyev` points towards the eye and `normalv`
+/// is the surface normal at `point`. A `point` that is `in_shadow`
+/// only gets the ambient contribution. The pattern of the `material`,
+/// if any, is looked up on `object`.
+///
+/// # Examples
+/// ```
+/// use ray_tracer::color::Color;
+/// use ray_tracer::light::{lighting, PointLight};
+/// use ray_tracer::material::Material;
+/// use ray_tracer::shapes::Sphere;
+/// use ray_tracer::tuple::{Point, Tuple, Vector};
+/// use ray_tracer::{color, point, vector};
+///
+/// let light = PointLight::new(point!(0, 0, -10), color!(1, 1, 1));
+/// let (eyev, normalv) = (vector!(0, 0, -1), vector!(0, 0, -1));
+/// let c = lighting(&Material::new(), &Sphere::new(), &light, point!(0, 0, 0), eyev, normalv, false);
+/// assert!(c == color!(1.9, 1.9, 1.9));
+/// ```
+pub fn lighting(
+    material: &Material,
+    object: &dyn Shape,
+    light: &PointLight,
+    point: Point,
+    eyev: Vector,
+    normalv: Vector,
+    in_shadow: bool,
+) -> Color {
+    let black = color!(0.0, 0.0, 0.0);
+    // Combine the surface color with the light's color
+    let color = match &material.pattern {
+        Some(pattern) => pattern.pattern_at_shape(object, point),
+        None => material.color,
+    };
+    let effective_color = color * light.intensity;
+    let lightv = (light.position - point).normalize();
+    let ambient = effective_color * material.ambient;
+    if in_shadow {
+        return ambient;
+    }
+
+    // A negative cosine means the light is on the other side of the surface
+    let light_dot_normal = lightv.dot(&normalv);
+    if light_dot_normal < 0.0 {
+        return ambient;
+    }
+    let diffuse = effective_color * material.diffuse * light_dot_normal;
+
+    // A negative cosine means the light reflects away from the eye
+    let reflectv = (-lightv).reflect(&normalv);
+    let reflect_dot_eye = reflectv.dot(&eyev);
+    let specular = if reflect_dot_eye <= 0.0 {
+        black
+    } else {
+        let factor = reflect_dot_eye.powf(material.shininess);
+        light.intensity * material.specular * factor
+    };
+
+    ambient + diffuse + specular
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::patterns::Stripe;
+    use crate::shapes::Sphere;
+    use crate::tuple::Tuple;
+    use crate::{point, vector};
+    use std::rc::Rc;
+
+    #[test]
+    fn create_point_light() {
+        let intensity = color!(1, 1, 1);
+        let position = point!(0, 0, 0);
+        let light = PointLight::new(position, intensity);
+        assert!(light.position == position);
+        assert!(light.intensity == intensity);
+    }
+
+    #[test]
+    fn eye_between_light_and_surface() {
+        let m = Material::new();
+        let position = point!(0, 0, 0);
+        let eyev = vector!(0, 0, -1);
+        let normalv = vector!(0, 0, -1);
+        let light = PointLight::new(point!(0, 0, -10), color!(1, 1, 1));
+        let result = lighting(&m, &Sphere::new(), &light, position, eyev, normalv, false);
+        assert_eq!(result, color!(1.9, 1.9, 1.9));
+    }
+
+    #[test]
+    fn eye_offset_45_degrees() {
+        let m = Material::new();
+        let position = point!(0, 0, 0);
+        let half = 2.0_f64.sqrt() / 2.0;
+        let eyev = vector!(0, half, -half);
+        let normalv = vector!(0, 0, -1);
+        let light = PointLight::new(point!(0, 0, -10), color!(1, 1, 1));
+        let result = lighting(&m, &Sphere::new(), &light, position, eyev, normalv, false);
+        assert_eq!(result, color!(1.0, 1.0, 1.0));
+    }
+
+    #[test]
+    fn light_offset_45_degrees() {
+        let m = Material::new();
+        let position = point!(0, 0, 0);
+        let eyev = vector!(0, 0, -1);
+        let normalv = vector!(0, 0, -1);
+        let light = PointLight::new(point!(0, 10, -10), color!(1, 1, 1));
+        let result = lighting(&m, &Sphere::new(), &light, position, eyev, normalv, false);
+        let expected = 0.1 + 0.9 * 2.0_f64.sqrt() / 2.0;
+        assert_eq!(result, color!(expected, expected, expected));
+    }
+
+    #[test]
+    fn eye_in_path_of_reflection() {
+        let m = Material::new();
+        let position = point!(0, 0, 0);
+        let half = 2.0_f64.sqrt() / 2.0;
+        let eyev = vector!(0, -half, -half);
+        let normalv = vector!(0, 0, -1);
+        let light = PointLight::new(point!(0, 10, -10), color!(1, 1, 1));
+        let result = lighting(&m, &Sphere::new(), &light, position, eyev, normalv, false);
+        let expected = 0.1 + 0.9 * half + 0.9;
+        assert_eq!(result, color!(expected, expected, expected));
+    }
+
+    #[test]
+    fn light_behind_surface() {
+        let m = Material::new();
+        let position = point!(0, 0, 0);
+        let eyev = vector!(0, 0, -1);
+        let normalv = vector!(0, 0, -1);
+        let light = PointLight::new(point!(0, 0, 10), color!(1, 1, 1));
+        let result = lighting(&m, &Sphere::new(), &light, position, eyev, normalv, false);
+        assert_eq!(result, color!(0.1, 0.1, 0.1));
+    }
+
+    #[test]
+    fn surface_in_shadow() {
+        let m = Material::new();
+        let position = point!(0, 0, 0);
+        let eyev = vector!(0, 0, -1);
+        let normalv = vector!(0, 0, -1);
+        let light = PointLight::new(point!(0, 0, -10), color!(1, 1, 1));
+        let result = lighting(&m, &Sphere::new(), &light, position, eyev, normalv, true);
+        assert_eq!(result, color!(0.1, 0.1, 0.1));
+    }
+
+    #[test]
+    fn lighting_with_pattern_applied() {
+        let m = Material {
+            pattern: Some(Rc::new(Stripe::new(color!(1, 1, 1), color!(0, 0, 0)))),
+            ambient: 1.0,
+            diffuse: 0.0,
+            specular: 0.0,
+            ..Material::new()
+        };
+        let s = Sphere::new();
+        let eyev = vector!(0, 0, -1);
+        let normalv = vector!(0, 0, -1);
+        let light = PointLight::new(point!(0, 0, -10), color!(1, 1, 1));
+        let c1 = lighting(&m, &s, &light, point!(0.9, 0, 0), eyev, normalv, false);
+        let c2 = lighting(&m, &s, &light, point!(1.1, 0, 0), eyev, normalv, false);
+        assert_eq!(c1, color!(1, 1, 1));
+        assert_eq!(c2, color!(0, 0, 0));
+    }
+}
@@ -0,0 +1,128 @@
+use crate::color; // for the macro
+use crate::color::Color; // for the type
+use crate::patterns::Pattern;
+use std::rc::Rc;
+
+/// The surface properties used by the Phong reflection model
+#[derive(Clone, Debug)]
+pub struct Material {
+    pub color: Color,
+    /// Colors the surface point by point, in place of `color`
+    ///
+    /// The pattern is shared, so that many objects can use it.
+    pub pattern: Option<Rc<dyn Pattern>>,
+    pub ambient: f64,
+    pub diffuse: f64,
+    pub specular: f64,
+    pub shininess: f64,
+    /// 0.0 for a matte surface, 1.0 for a perfect mirror
+    pub reflective: f64,
+    /// 0.0 for an opaque surface, 1.0 for a perfectly clear one
+    pub transparency: f64,
+    /// How much light bends when entering the material, 1.0 for a
+    /// vacuum and about 1.5 for glass
+    pub refractive_index: f64,
+}
+
+impl Material {
+    /// Create a white `Material` with the default Phong parameters
+    pub fn new() -> Material {
+        Material {
+            color: color!(1.0, 1.0, 1.0),
+            pattern: None,
+            ambient: 0.1,
+            diffuse: 0.9,
+            specular: 0.9,
+            shininess: 200.0,
+            reflective: 0.0,
+            transparency: 0.0,
+            refractive_index: 1.0,
+        }
+    }
+}
+
+/// Create a `Material` that looks like clear glass
+///
+/// # Examples
+/// ```
+/// use ray_tracer::material::glass;
+///
+/// let m = glass();
+/// assert_eq!(m.transparency, 1.0);
+/// assert_eq!(m.refractive_index, 1.5);
+/// ```
+pub fn glass() -> Material {
+    Material {
+        transparency: 1.0,
+        refractive_index: 1.5,
+        ..Material::new()
+    }
+}
+
+impl PartialEq for Material {
+    /// Two `Material`s are equal if their parameters are, and if they
+    /// share the very same pattern
+    fn eq(&self, other: &Self) -> bool {
+        let same_pattern = match (&self.pattern, &other.pattern) {
+            (Some(a), Some(b)) => Rc::ptr_eq(a, b),
+            (None, None) => true,
+            _ => false,
+        };
+
+        same_pattern
+            && self.color == other.color
+            && self.ambient == other.ambient
+            && self.diffuse == other.diffuse
+            && self.specular == other.specular
+            && self.shininess == other.shininess
+            && self.reflective == other.reflective
+            && self.transparency == other.transparency
+            && self.refractive_index == other.refractive_index
+    }
+}
+
+impl Default for Material {
+    fn default() -> Self {
+        Material::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::patterns::Stripe;
+
+    #[test]
+    fn default_material() {
+        let m = Material::new();
+        assert_eq!(m.color, color!(1, 1, 1));
+        assert!(m.pattern.is_none());
+        assert_eq!(m.ambient, 0.1);
+        assert_eq!(m.diffuse, 0.9);
+        assert_eq!(m.specular, 0.9);
+        assert_eq!(m.shininess, 200.0);
+        assert_eq!(m.reflective, 0.0);
+        assert_eq!(m.transparency, 0.0);
+        assert_eq!(m.refractive_index, 1.0);
+    }
+
+    #[test]
+    fn materials_with_patterns() {
+        let stripes: Rc<dyn Pattern> = Rc::new(Stripe::new(color!(1, 1, 1), color!(0, 0, 0)));
+        let m1 = Material {
+            pattern: Some(Rc::clone(&stripes)),
+            ..Material::new()
+        };
+        let m2 = m1.clone();
+        assert_eq!(m1, m2);
+
+        // An identical pattern that is not shared makes a different
+        // material
+        let m3 = Material {
+            pattern: Some(Rc::new(Stripe::new(color!(1, 1, 1), color!(0, 0, 0)))),
+            ..Material::new()
+        };
+        assert_ne!(m1, m3);
+        assert_ne!(m1, Material::new());
+    }
+}
@@ -0,0 +1,523 @@
+//! Coherent noise, random looking values that vary smoothly in space
+//!
+//! Every function of a `Noise` is deterministic: the same seed always
+//! gives the same values, on every run and every platform.
+use crate::tuple::{Point, Tuple, Vector};
+
+/// Ken Perlin's reference permutation of the numbers 0 to 255
+const PERMUTATION: [u8; 256] = [
+    151, 160, 137, 91, 90, 15, 131, 13, 201, 95, 96, 53, 194, 233, 7, 225, 140, 36, 103, 30, 69,
+    142, 8, 99, 37, 240, 21, 10, 23, 190, 6, 148, 247, 120, 234, 75, 0, 26, 197, 62, 94, 252, 219,
+    203, 117, 35, 11, 32, 57, 177, 33, 88, 237, 149, 56, 87, 174, 20, 125, 136, 171, 168, 68, 175,
+    74, 165, 71, 134, 139, 48, 27, 166, 77, 146, 158, 231, 83, 111, 229, 122, 60, 211, 133, 230,
+    220, 105, 92, 41, 55, 46, 245, 40, 244, 102, 143, 54, 65, 25, 63, 161, 1, 216, 80, 73, 209, 76,
+    132, 187, 208, 89, 18, 169, 200, 196, 135, 130, 116, 188, 159, 86, 164, 100, 109, 198, 173,
+    186, 3, 64, 52, 217, 226, 250, 124, 123, 5, 202, 38, 147, 118, 126, 255, 82, 85, 212, 207, 206,
+    59, 227, 47, 16, 58, 17, 182, 189, 28, 42, 223, 183, 170, 213, 119, 248, 152, 2, 44, 154, 163,
+    70, 221, 153, 101, 155, 167, 43, 172, 9, 129, 22, 39, 253, 19, 98, 108, 110, 79, 113, 224, 232,
+    178, 185, 112, 104, 218, 246, 97, 228, 251, 34, 242, 193, 238, 210, 144, 12, 191, 179, 162,
+    241, 81, 51, 145, 235, 249, 14, 239, 107, 49, 192, 214, 31, 181, 199, 106, 157, 184, 84, 204,
+    176, 115, 121, 50, 45, 127, 4, 150, 254, 138, 236, 205, 93, 222, 114, 67, 29, 24, 72, 243, 141,
+    128, 195, 78, 66, 215, 61, 156, 180,
+];
+
+/// The twelve gradients pointing to the edges of a cube, used by
+/// simplex noise
+const GRADIENTS: [(f64, f64, f64); 12] = [
+    (1.0, 1.0, 0.0),
+    (-1.0, 1.0, 0.0),
+    (1.0, -1.0, 0.0),
+    (-1.0, -1.0, 0.0),
+    (1.0, 0.0, 1.0),
+    (-1.0, 0.0, 1.0),
+    (1.0, 0.0, -1.0),
+    (-1.0, 0.0, -1.0),
+    (0.0, 1.0, 1.0),
+    (0.0, -1.0, 1.0),
+    (0.0, 1.0, -1.0),
+    (0.0, -1.0, -1.0),
+];
+
+/// The smoothstep curve `6t^5 - 15t^4 + 10t^3`, which has zero first
+/// and second derivatives at 0 and 1
+fn fade(t: f64) -> f64 {
+    t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+fn lerp(t: f64, a: f64, b: f64) -> f64 {
+    a + t * (b - a)
+}
+
+/// The dot product of `(x, y, z)` with one of the twelve gradients
+/// pointing to the edges of a cube, picked by `hash`
+fn grad(hash: u8, x: f64, y: f64, z: f64) -> f64 {
+    let h = hash & 15;
+    let u = if h < 8 { x } else { y };
+    let v = if h < 4 {
+        y
+    } else if h == 12 || h == 14 {
+        x
+    } else {
+        z
+    };
+
+    (if h & 1 == 0 { u } else { -u }) + (if h & 2 == 0 { v } else { -v })
+}
+
+/// The SplitMix64 generator, only used to shuffle the permutation
+/// table so that seeds give the same table everywhere
+fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// A source of coherent noise, defined by a permutation of the numbers
+/// 0 to 255
+///
+/// # Examples
+/// ```
+/// use ray_tracer::noise::Noise;
+/// use ray_tracer::tuple::{Point, Tuple};
+/// use ray_tracer::point;
+///
+/// let p = point!(1.5, 2.25, 3.75);
+/// assert_eq!(Noise::new(7).perlin(p), Noise::new(7).perlin(p));
+/// assert_ne!(Noise::new(7).perlin(p), Noise::new(8).perlin(p));
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Noise {
+    permutation: [u8; 256],
+}
+
+impl Noise {
+    /// Create a `Noise` with a permutation shuffled from `seed`
+    pub fn new(seed: u64) -> Noise {
+        let mut permutation = [0u8; 256];
+        for (i, value) in permutation.iter_mut().enumerate() {
+            *value = i as u8;
+        }
+
+        // Fisher-Yates shuffle
+        let mut state = seed;
+        for i in (1..permutation.len()).rev() {
+            let j = (splitmix64(&mut state) % (i as u64 + 1)) as usize;
+            permutation.swap(i, j);
+        }
+
+        Noise { permutation }
+    }
+
+    /// Create a `Noise` with Ken Perlin's reference permutation
+    pub fn reference() -> Noise {
+        Noise {
+            permutation: PERMUTATION,
+        }
+    }
+
+    fn p(&self, i: usize) -> usize {
+        self.permutation[i & 255] as usize
+    }
+
+    /// Hash the integer coordinates of a lattice point into 0..=255
+    fn hash(&self, x: i64, y: i64, z: i64) -> usize {
+        let wrap = |i: i64| i.rem_euclid(256) as usize;
+        self.p(self.p(self.p(wrap(x)) + wrap(y)) + wrap(z))
+    }
+
+    /// Compute Perlin's improved gradient noise at `point`
+    ///
+    /// The result is roughly between -1.0 and 1.0, and is always 0.0 at
+    /// integer coordinates.
+    ///
+    /// # Examples
+    /// ```
+    /// use ray_tracer::noise::Noise;
+    /// use ray_tracer::tuple::{Point, Tuple};
+    /// use ray_tracer::point;
+    ///
+    /// let noise = Noise::reference();
+    /// assert_eq!(noise.perlin(point!(1, 2, 3)), 0.0);
+    /// assert!(noise.perlin(point!(1.5, 2.25, 3.75)).abs() <= 1.0);
+    /// ```
+    pub fn perlin(&self, point: Point) -> f64 {
+        let p = |i: usize| self.p(i);
+        let g = |i: usize, x: f64, y: f64, z: f64| grad(p(i) as u8, x, y, z);
+
+        // The unit cube that contains the point
+        let (xf, yf, zf) = (point.x.floor(), point.y.floor(), point.z.floor());
+        let (xi, yi, zi) = (
+            xf.rem_euclid(256.0) as usize,
+            yf.rem_euclid(256.0) as usize,
+            zf.rem_euclid(256.0) as usize,
+        );
+        // The position inside of that cube
+        let (x, y, z) = (point.x - xf, point.y - yf, point.z - zf);
+        let (u, v, w) = (fade(x), fade(y), fade(z));
+
+        // Hash the coordinates of the eight corners
+        let a = p(xi) + yi;
+        let (aa, ab) = (p(a) + zi, p(a + 1) + zi);
+        let b = p(xi + 1) + yi;
+        let (ba, bb) = (p(b) + zi, p(b + 1) + zi);
+
+        lerp(
+            w,
+            lerp(
+                v,
+                lerp(u, g(aa, x, y, z), g(ba, x - 1.0, y, z)),
+                lerp(u, g(ab, x, y - 1.0, z), g(bb, x - 1.0, y - 1.0, z)),
+            ),
+            lerp(
+                v,
+                lerp(u, g(aa + 1, x, y, z - 1.0), g(ba + 1, x - 1.0, y, z - 1.0)),
+                lerp(
+                    u,
+                    g(ab + 1, x, y - 1.0, z - 1.0),
+                    g(bb + 1, x - 1.0, y - 1.0, z - 1.0),
+                ),
+            ),
+        )
+    }
+
+    /// Compute simplex noise at `point`, between -1.0 and 1.0
+    ///
+    /// Simplex noise is cheaper than `perlin` and has no visible
+    /// alignment with the axes.
+    pub fn simplex(&self, point: Point) -> f64 {
+        // Skew and unskew factors for three dimensions
+        const F3: f64 = 1.0 / 3.0;
+        const G3: f64 = 1.0 / 6.0;
+
+        // Find the skewed cell and the simplex corner that contains
+        // the point
+        let s = (point.x + point.y + point.z) * F3;
+        let (i, j, k) = (
+            (point.x + s).floor(),
+            (point.y + s).floor(),
+            (point.z + s).floor(),
+        );
+        let t = (i + j + k) * G3;
+        let x0 = point.x - (i - t);
+        let y0 = point.y - (j - t);
+        let z0 = point.z - (k - t);
+
+        // Which of the six simplices of the cell holds the point
+        let ((i1, j1, k1), (i2, j2, k2)) = if x0 >= y0 {
+            if y0 >= z0 {
+                ((1, 0, 0), (1, 1, 0))
+            } else if x0 >= z0 {
+                ((1, 0, 0), (1, 0, 1))
+            } else {
+                ((0, 0, 1), (1, 0, 1))
+            }
+        } else if y0 < z0 {
+            ((0, 0, 1), (0, 1, 1))
+        } else if x0 < z0 {
+            ((0, 1, 0), (0, 1, 1))
+        } else {
+            ((0, 1, 0), (1, 1, 0))
+        };
+
+        let corners = [
+            ((0, 0, 0), (x0, y0, z0)),
+            (
+                (i1, j1, k1),
+                (
+                    x0 - i1 as f64 + G3,
+                    y0 - j1 as f64 + G3,
+                    z0 - k1 as f64 + G3,
+                ),
+            ),
+            (
+                (i2, j2, k2),
+                (
+                    x0 - i2 as f64 + 2.0 * G3,
+                    y0 - j2 as f64 + 2.0 * G3,
+                    z0 - k2 as f64 + 2.0 * G3,
+                ),
+            ),
+            (
+                (1, 1, 1),
+                (
+                    x0 - 1.0 + 3.0 * G3,
+                    y0 - 1.0 + 3.0 * G3,
+                    z0 - 1.0 + 3.0 * G3,
+                ),
+            ),
+        ];
+
+        // Wrapped like the hash, so that huge coordinates can't overflow
+        let (i, j, k) = (
+            i.rem_euclid(256.0) as i64,
+            j.rem_euclid(256.0) as i64,
+            k.rem_euclid(256.0) as i64,
+        );
+        let total: f64 = corners
+            .iter()
+            .map(|&((di, dj, dk), (x, y, z))| {
+                let t = 0.6 - x * x - y * y - z * z;
+                if t < 0.0 {
+                    return 0.0;
+                }
+                let (gx, gy, gz) = GRADIENTS[self.hash(i + di, j + dj, k + dk) % 12];
+                t.powi(4) * (gx * x + gy * y + gz * z)
+            })
+            .sum();
+
+        // Scale the result to stay within -1.0 and 1.0
+        (32.0 * total).clamp(-1.0, 1.0)
+    }
+
+    /// Compute fractal Brownian motion at `point`, the sum of `octaves`
+    /// layers of `perlin` noise
+    ///
+    /// Each layer has its frequency multiplied by `lacunarity` and its
+    /// amplitude by `gain`, usually 2.0 and 0.5. The result is
+    /// normalized to stay roughly between -1.0 and 1.0.
+    pub fn fbm(&self, point: Point, octaves: usize, lacunarity: f64, gain: f64) -> f64 {
+        let mut total = 0.0;
+        let mut frequency = 1.0;
+        let mut amplitude = 1.0;
+        let mut max_amplitude = 0.0;
+
+        for _ in 0..octaves {
+            total += self.perlin(point * frequency) * amplitude;
+            max_amplitude += amplitude;
+            frequency *= lacunarity;
+            amplitude *= gain;
+        }
+
+        if max_amplitude == 0.0 {
+            0.0
+        } else {
+            total / max_amplitude
+        }
+    }
+
+    /// Compute turbulence at `point`, like `fbm` with a lacunarity of
+    /// 2.0 and a gain of 0.5 but summing the absolute value of each
+    /// layer
+    ///
+    /// The creases where the noise crosses zero make it look like fire
+    /// or marble veins. The result is between 0.0 and 1.0.
+    pub fn turbulence(&self, point: Point, octaves: usize) -> f64 {
+        let mut total = 0.0;
+        let mut frequency = 1.0;
+        let mut amplitude = 1.0;
+        let mut max_amplitude = 0.0;
+
+        for _ in 0..octaves {
+            total += self.perlin(point * frequency).abs() * amplitude;
+            max_amplitude += amplitude;
+            frequency *= 2.0;
+            amplitude *= 0.5;
+        }
+
+        if max_amplitude == 0.0 {
+            0.0
+        } else {
+            (total / max_amplitude).min(1.0)
+        }
+    }
+
+    /// Compute Worley, or cellular, noise at `point`: the distance to
+    /// the closest of a set of feature points scattered one per unit
+    /// cube
+    ///
+    /// The result is 0.0 on the feature points and grows towards the
+    /// edges of the cells.
+    pub fn worley(&self, point: Point) -> f64 {
+        // The unit cube that contains the point, and the position inside
+        // of it
+        let (xf, yf, zf) = (point.x.floor(), point.y.floor(), point.z.floor());
+        let local = Vector::new(point.x - xf, point.y - yf, point.z - zf);
+        // Wrapped like the hash, so that huge coordinates can't overflow
+        let (xi, yi, zi) = (
+            xf.rem_euclid(256.0) as i64,
+            yf.rem_euclid(256.0) as i64,
+            zf.rem_euclid(256.0) as i64,
+        );
+
+        let mut closest = f64::INFINITY;
+        // The closest feature point is in this cell or a neighbor
+        for dx in -1..=1 {
+            for dy in -1..=1 {
+                for dz in -1..=1 {
+                    let cell = Vector::new(dx as f64, dy as f64, dz as f64);
+                    let feature = cell + self.feature_offset(xi + dx, yi + dy, zi + dz);
+                    closest = closest.min((feature - local).magnitude());
+                }
+            }
+        }
+
+        closest
+    }
+
+    /// The position of the feature point of the unit cube with its lowest
+    /// corner at `(x, y, z)`, relative to that corner, used by `worley`
+    fn feature_offset(&self, x: i64, y: i64, z: i64) -> Vector {
+        // Different lattice points give three uncorrelated offsets
+        let offset = |salt: i64| (self.hash(x, y, z + salt) as f64 + 0.5) / 256.0;
+
+        Vector::new(offset(0), offset(101), offset(211))
+    }
+}
+
+impl Default for Noise {
+    /// The reference permutation, see `Noise::reference`
+    fn default() -> Self {
+        Noise::reference()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::point;
+
+    /// Points scattered over a few cells, away from the lattice
+    fn samples() -> impl Iterator<Item = Point> {
+        (0..500).map(|i| {
+            let t = i as f64 * 0.137;
+            point!(t, t * 0.7 - 3.0, 11.0 - t * 1.3)
+        })
+    }
+
+    #[test]
+    fn splitmix64_matches_the_reference_implementation() {
+        let mut state = 0;
+        assert_eq!(splitmix64(&mut state), 0xE220_A839_7B1D_CDAF);
+        assert_eq!(splitmix64(&mut state), 0x6E78_9E6A_A1B9_65F4);
+    }
+
+    #[test]
+    fn seeded_permutation_is_a_permutation() {
+        let noise = Noise::new(1234);
+        let mut values = noise.permutation.to_vec();
+        values.sort();
+        assert!(values.iter().enumerate().all(|(i, &v)| v as usize == i));
+        assert_ne!(noise, Noise::reference());
+    }
+
+    #[test]
+    fn seeds_are_deterministic() {
+        assert_eq!(Noise::new(42), Noise::new(42));
+        assert_ne!(Noise::new(42), Noise::new(43));
+        let p = point!(0.3, 1.7, -4.2);
+        assert_eq!(Noise::new(42).simplex(p), Noise::new(42).simplex(p));
+        assert_eq!(Noise::new(42).worley(p), Noise::new(42).worley(p));
+    }
+
+    #[test]
+    fn seeded_noise_is_stable_across_runs() {
+        // Golden values, any change here breaks rendered images
+        let noise = Noise::new(42);
+        assert_eq!(
+            &noise.permutation[..8],
+            &[203, 217, 124, 199, 53, 101, 223, 240]
+        );
+    }
+
+    #[test]
+    fn perlin_is_zero_on_the_lattice() {
+        let noise = Noise::new(5);
+        for p in [point!(0, 0, 0), point!(-3, 7, 12), point!(255, 256, -257)] {
+            assert_eq!(noise.perlin(p), 0.0);
+        }
+    }
+
+    #[test]
+    fn perlin_is_bounded() {
+        let noise = Noise::reference();
+        assert!(samples().all(|p| (-1.0..=1.0).contains(&noise.perlin(p))));
+    }
+
+    #[test]
+    fn perlin_varies_smoothly() {
+        let noise = Noise::reference();
+        let p = point!(1.3, 4.7, -2.2);
+        let q = point!(1.3001, 4.7, -2.2);
+        assert!((noise.perlin(p) - noise.perlin(q)).abs() < 1e-3);
+        assert_ne!(noise.perlin(p), noise.perlin(point!(2.3, 4.7, -2.2)));
+    }
+
+    // The point used by the reference implementation is not meant to
+    // be PI
+    #[allow(clippy::approx_constant)]
+    #[test]
+    fn perlin_matches_the_reference_implementation() {
+        let n = Noise::reference().perlin(point!(3.14, 42, 7));
+        assert!((n - 0.13691995878400012).abs() < 1e-12);
+    }
+
+    #[test]
+    fn simplex_is_bounded_and_smooth() {
+        let noise = Noise::new(9);
+        assert!(samples().all(|p| (-1.0..=1.0).contains(&noise.simplex(p))));
+        assert!(samples().any(|p| noise.simplex(p).abs() > 0.3));
+
+        let p = point!(1.3, 4.7, -2.2);
+        let q = point!(1.3001, 4.7, -2.2);
+        assert!((noise.simplex(p) - noise.simplex(q)).abs() < 1e-3);
+    }
+
+    #[test]
+    fn fbm_with_one_octave_is_perlin() {
+        let noise = Noise::new(3);
+        for p in samples().take(20) {
+            assert_eq!(noise.fbm(p, 1, 2.0, 0.5), noise.perlin(p));
+        }
+        assert_eq!(noise.fbm(point!(0.5, 0.5, 0.5), 0, 2.0, 0.5), 0.0);
+    }
+
+    #[test]
+    fn fbm_adds_detail() {
+        let noise = Noise::new(3);
+        assert!(samples().all(|p| (-1.0..=1.0).contains(&noise.fbm(p, 6, 2.0, 0.5))));
+        let differs = samples().any(|p| noise.fbm(p, 6, 2.0, 0.5) != noise.fbm(p, 1, 2.0, 0.5));
+        assert!(differs);
+    }
+
+    #[test]
+    fn turbulence_is_positive() {
+        let noise = Noise::new(11);
+        assert!(samples().all(|p| (0.0..=1.0).contains(&noise.turbulence(p, 5))));
+        assert!(samples().any(|p| noise.turbulence(p, 5) > 0.0));
+    }
+
+    #[test]
+    fn worley_is_zero_on_feature_points() {
+        let noise = Noise::new(17);
+        let offset = noise.feature_offset(2, -3, 5);
+        let feature = point!(2.0 + offset.x, -3.0 + offset.y, 5.0 + offset.z);
+        assert_eq!(noise.worley(feature), 0.0);
+        // Feature points stay inside their cell
+        for o in [offset.x, offset.y, offset.z] {
+            assert!((0.0..1.0).contains(&o));
+        }
+    }
+
+    #[test]
+    fn worley_is_a_distance() {
+        let noise = Noise::new(17);
+        for p in samples() {
+            let d = noise.worley(p);
+            // The feature point of the cell itself is never farther
+            // than the diagonal of the cell
+            assert!((0.0..=3.0_f64.sqrt()).contains(&d));
+        }
+    }
+
+    #[test]
+    fn huge_coordinates_dont_overflow() {
+        let noise = Noise::default();
+        for p in [point!(1e300, 0, 0), point!(0, -1e300, 1e20)] {
+            assert!(!noise.perlin(p).is_nan());
+            assert!(!noise.simplex(p).is_nan());
+            assert!((0.0..=3.0_f64.sqrt()).contains(&noise.worley(p)));
+        }
+    }
+}
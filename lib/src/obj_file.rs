@@ -0,0 +1,470 @@
+//! A parser for Wavefront OBJ files
+use crate::shapes::{Bvh, Group, Shape, SmoothTriangle, Triangle};
+use crate::tuple::{Point, Tuple, Vector};
+use std::error::Error;
+use std::fmt;
+
+/// The errors that make an OBJ file impossible to parse
+#[derive(Clone, Debug, PartialEq)]
+pub enum ObjError {
+    /// A face refers to a vertex, texture vertex or normal that does
+    /// not exist
+    InvalidIndex { line: usize, index: String },
+    /// A statement that is known but has the wrong number of values,
+    /// or values that are not numbers
+    MalformedLine { line: usize, content: String },
+}
+
+impl fmt::Display for ObjError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ObjError::InvalidIndex { line, index } => {
+                write!(f, "line {}: invalid index `{}`", line, index)
+            }
+            ObjError::MalformedLine { line, content } => {
+                write!(f, "line {}: malformed statement `{}`", line, content)
+            }
+        }
+    }
+}
+
+impl Error for ObjError {}
+
+/// A named group of triangles, from a `g` or `o` statement
+#[derive(Debug, Default)]
+pub struct ObjGroup {
+    pub name: String,
+    pub triangles: Vec<Box<dyn Shape>>,
+}
+
+/// The contents of an OBJ file
+#[derive(Debug, Default)]
+pub struct ObjFile {
+    pub vertices: Vec<Point>,
+    pub normals: Vec<Vector>,
+    pub texture_vertices: Vec<(f64, f64)>,
+    /// The triangles found before any `g` or `o` statement
+    pub default_group: ObjGroup,
+    pub groups: Vec<ObjGroup>,
+    /// The line numbers and contents of every line that was not
+    /// understood by the parser
+    pub ignored: Vec<(usize, String)>,
+}
+
+impl ObjFile {
+    /// Convert the file into a single `Group`, where every named group
+    /// becomes a child `Group`, and groups without any triangle are
+    /// left out
+    ///
+    /// Large meshes should be divided afterwards with `Shape::divide`,
+    /// so that rays don't test every triangle.
+    pub fn into_group(self) -> Group {
+        let mut group = Group::new();
+        for triangle in self.default_group.triangles {
+            group.add_child(triangle);
+        }
+        for obj_group in self.groups {
+            if obj_group.triangles.is_empty() {
+                continue;
+            }
+            let mut child = Group::new();
+            for triangle in obj_group.triangles {
+                child.add_child(triangle);
+            }
+            group.add_child(Box::new(child));
+        }
+
+        group
+    }
+
+    /// Put every triangle of the file in a single `Bvh`, with at most
+    /// `max_leaf_size` triangles in each leaf
+    ///
+    /// Leaves can only be larger when their triangles all have the
+    /// same center, see `Bvh::new`. The named groups are flattened,
+    /// since the triangles are only organized by their position from
+    /// then on.
+    ///
+    /// # Panics
+    /// If `max_leaf_size` is zero.
+    pub fn into_bvh(self, max_leaf_size: usize) -> Bvh {
+        let triangles = self
+            .groups
+            .into_iter()
+            .flat_map(|group| group.triangles)
+            .chain(self.default_group.triangles)
+            .collect();
+
+        Bvh::new(triangles, max_leaf_size)
+    }
+
+    /// The group that receives the faces being parsed
+    fn current_group(&mut self) -> &mut ObjGroup {
+        self.groups.last_mut().unwrap_or(&mut self.default_group)
+    }
+}
+
+/// Parse the numbers of a statement, which must have between `required`
+/// and `N` of them, the missing ones being 0.0
+fn parse_numbers<const N: usize>(
+    values: &[&str],
+    required: usize,
+    line: usize,
+    content: &str,
+) -> Result<[f64; N], ObjError> {
+    let malformed = || ObjError::MalformedLine {
+        line,
+        content: content.to_string(),
+    };
+    if values.len() < required || values.len() > N {
+        return Err(malformed());
+    }
+
+    let mut numbers = [0.0; N];
+    for (number, value) in numbers.iter_mut().zip(values) {
+        *number = value.parse().map_err(|_| malformed())?;
+    }
+
+    Ok(numbers)
+}
+
+/// Convert a 1-based (or negative, relative to the end) OBJ index into
+/// an index for a list of `len` elements
+fn resolve_index(index: &str, len: usize, line: usize) -> Result<usize, ObjError> {
+    let invalid = || ObjError::InvalidIndex {
+        line,
+        index: index.to_string(),
+    };
+    let value: i64 = index.parse().map_err(|_| invalid())?;
+
+    let resolved = if value > 0 {
+        value - 1
+    } else {
+        len as i64 + value
+    };
+    if value == 0 || resolved < 0 || resolved >= len as i64 {
+        return Err(invalid());
+    }
+
+    Ok(resolved as usize)
+}
+
+/// Parse the text of an OBJ file
+///
+/// Vertices (`v`), texture vertices (`vt`), normals (`vn`), faces
+/// (`f`) and groups (`g` and `o`) are supported. Polygons are split
+/// into a fan of triangles, and faces that give a normal for every
+/// vertex become `SmoothTriangle`s. Any other line, besides blank lines
+/// and comments, is ignored and reported in `ObjFile::ignored`.
+///
+/// # Examples
+/// ```
+/// use ray_tracer::obj_file::parse_obj_file;
+///
+/// let input = "v -1 1 0\nv -1 0 0\nv 1 0 0\nv 1 1 0\nf 1 2 3 4\n";
+/// let obj = parse_obj_file(input).unwrap();
+/// assert_eq!(obj.default_group.triangles.len(), 2);
+/// ```
+pub fn parse_obj_file(input: &str) -> Result<ObjFile, ObjError> {
+    let mut obj = ObjFile::default();
+
+    for (number, content) in input.lines().enumerate() {
+        let line = number + 1;
+        let mut words = content.split_whitespace();
+        let statement = words.next();
+        let values: Vec<&str> = words.collect();
+
+        match statement {
+            Some("v") => {
+                // The optional weight is only used by rational curves
+                let [x, y, z, _] = parse_numbers(&values, 3, line, content)?;
+                obj.vertices.push(Point::new(x, y, z));
+            }
+            Some("vn") => {
+                let [x, y, z] = parse_numbers(&values, 3, line, content)?;
+                obj.normals.push(Vector::new(x, y, z));
+            }
+            Some("vt") => {
+                // The optional third coordinate is not used
+                let [u, v, _] = parse_numbers(&values, 1, line, content)?;
+                obj.texture_vertices.push((u, v));
+            }
+            Some("f") => {
+                let triangles = parse_face(&obj, &values, line, content)?;
+                obj.current_group().triangles.extend(triangles);
+            }
+            Some("g") | Some("o") => obj.groups.push(ObjGroup {
+                name: values.join(" "),
+                triangles: Vec::new(),
+            }),
+            // Blank lines and comments are not worth reporting
+            None => {}
+            Some(word) if word.starts_with('#') => {}
+            _ => obj.ignored.push((line, content.to_string())),
+        }
+    }
+
+    Ok(obj)
+}
+
+/// Parse the vertices of a face, and split it into triangles
+fn parse_face(
+    obj: &ObjFile,
+    values: &[&str],
+    line: usize,
+    content: &str,
+) -> Result<Vec<Box<dyn Shape>>, ObjError> {
+    if values.len() < 3 {
+        return Err(ObjError::MalformedLine {
+            line,
+            content: content.to_string(),
+        });
+    }
+
+    let mut vertices = Vec::new();
+    let mut normals = Vec::new();
+    for value in values {
+        // Each vertex is `v`, `v/vt`, `v//vn` or `v/vt/vn`
+        let mut indices = value.split('/');
+        let vertex = indices.next().unwrap_or_default();
+        vertices.push(obj.vertices[resolve_index(vertex, obj.vertices.len(), line)?]);
+
+        if let Some(texture) = indices.next().filter(|t| !t.is_empty()) {
+            resolve_index(texture, obj.texture_vertices.len(), line)?;
+        }
+        if let Some(normal) = indices.next().filter(|n| !n.is_empty()) {
+            normals.push(obj.normals[resolve_index(normal, obj.normals.len(), line)?]);
+        }
+    }
+
+    let smooth = normals.len() == vertices.len();
+    let mut triangles: Vec<Box<dyn Shape>> = Vec::new();
+    for i in 1..vertices.len() - 1 {
+        if smooth {
+            triangles.push(Box::new(SmoothTriangle::new(
+                vertices[0],
+                vertices[i],
+                vertices[i + 1],
+                normals[0],
+                normals[i],
+                normals[i + 1],
+            )));
+        } else {
+            triangles.push(Box::new(Triangle::new(
+                vertices[0],
+                vertices[i],
+                vertices[i + 1],
+            )));
+        }
+    }
+
+    Ok(triangles)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intersection::Intersection;
+    use crate::ray::Ray;
+    use crate::{point, vector};
+
+    #[test]
+    fn ignore_unrecognized_lines() {
+        let input = "There was a young lady named Bright\n\
+                     who traveled much faster than light.\n\
+                     She set out one day\n\
+                     in a relative way,\n\
+                     and came back the previous night.";
+        let obj = parse_obj_file(input).unwrap();
+        assert_eq!(obj.ignored.len(), 5);
+        assert_eq!(obj.ignored[0].0, 1);
+        assert_eq!(obj.ignored[4].1, "and came back the previous night.");
+    }
+
+    #[test]
+    fn vertex_records() {
+        let input = "# a comment\nv -1 1 0\nv -1.0000 0.5000 0.0000\nv 1 0 0\nv 1 1 0";
+        let obj = parse_obj_file(input).unwrap();
+        assert!(obj.vertices[0] == point!(-1, 1, 0));
+        assert!(obj.vertices[1] == point!(-1, 0.5, 0));
+        assert!(obj.vertices[2] == point!(1, 0, 0));
+        assert!(obj.vertices[3] == point!(1, 1, 0));
+        assert!(obj.ignored.is_empty());
+    }
+
+    #[test]
+    fn parse_triangle_faces() {
+        let input = "v -1 1 0\nv -1 0 0\nv 1 0 0\nv 1 1 0\n\nf 1 2 3\nf 1 3 4";
+        let obj = parse_obj_file(input).unwrap();
+        let triangles = &obj.default_group.triangles;
+        assert_eq!(triangles.len(), 2);
+
+        // The first triangle is hit between p1, p2 and p3
+        let r = Ray::new(point!(-0.5, 0.5, -1), vector!(0, 0, 1));
+        assert_eq!(triangles[0].intersect(&r).len(), 1);
+        assert!(triangles[1].intersect(&r).is_empty());
+        // Blank lines are skipped without being reported
+        assert!(obj.ignored.is_empty());
+    }
+
+    #[test]
+    fn triangulate_polygons() {
+        let input = "v -1 1 0\nv -1 0 0\nv 1 0 0\nv 1 1 0\nv 0 2 0\nf 1 2 3 4 5";
+        let obj = parse_obj_file(input).unwrap();
+        let triangles = &obj.default_group.triangles;
+        assert_eq!(triangles.len(), 3);
+
+        // Every triangle shares the first vertex
+        let expected = [
+            Triangle::new(obj.vertices[0], obj.vertices[1], obj.vertices[2]),
+            Triangle::new(obj.vertices[0], obj.vertices[2], obj.vertices[3]),
+            Triangle::new(obj.vertices[0], obj.vertices[3], obj.vertices[4]),
+        ];
+        for (triangle, expected) in triangles.iter().zip(expected.iter()) {
+            assert!(triangle.bounds() == expected.bounds());
+        }
+    }
+
+    #[test]
+    fn triangles_in_groups() {
+        let input = "v -1 1 0\nv -1 0 0\nv 1 0 0\nv 1 1 0\n\
+                     g FirstGroup\nf 1 2 3\n\
+                     o SecondGroup\nf 1 3 4";
+        let obj = parse_obj_file(input).unwrap();
+        assert!(obj.default_group.triangles.is_empty());
+        assert_eq!(obj.groups.len(), 2);
+        assert_eq!(obj.groups[0].name, "FirstGroup");
+        assert_eq!(obj.groups[0].triangles.len(), 1);
+        assert_eq!(obj.groups[1].name, "SecondGroup");
+        assert_eq!(obj.groups[1].triangles.len(), 1);
+    }
+
+    #[test]
+    fn vertex_normal_and_texture_records() {
+        let input = "vn 0 0 1\nvn 0.707 0 -0.707\nvn 1 2 3\nvt 0.5 0.25\nvt 0.1 0.2 0.0";
+        let obj = parse_obj_file(input).unwrap();
+        assert!(obj.normals[0] == vector!(0, 0, 1));
+        assert!(obj.normals[1] == vector!(0.707, 0, -0.707));
+        assert!(obj.normals[2] == vector!(1, 2, 3));
+        assert_eq!(obj.texture_vertices, vec![(0.5, 0.25), (0.1, 0.2)]);
+    }
+
+    #[test]
+    fn optional_vertex_coordinates() {
+        let input = "v 1 2 3 0.5\nvt 0.75\nvt 0.25 0.5 1";
+        let obj = parse_obj_file(input).unwrap();
+        assert!(obj.vertices[0] == point!(1, 2, 3));
+        assert_eq!(obj.texture_vertices, vec![(0.75, 0.0), (0.25, 0.5)]);
+        assert!(obj.ignored.is_empty());
+    }
+
+    #[test]
+    fn faces_with_normals() {
+        let input = "v 0 1 0\nv -1 0 0\nv 1 0 0\n\
+                     vn -1 0 0\nvn 1 0 0\nvn 0 1 0\nvt 0 0\n\
+                     f 1//3 2//1 3//2\nf 1/1/3 2/1/1 3/1/2";
+        let obj = parse_obj_file(input).unwrap();
+        let triangles = &obj.default_group.triangles;
+        assert_eq!(triangles.len(), 2);
+
+        // Both faces are the same smooth triangle, so the normal is
+        // interpolated from the vertex normals
+        let r = Ray::new(point!(-0.2, 0.3, -2), vector!(0, 0, 1));
+        for triangle in triangles {
+            let xs = triangle.intersect(&r);
+            let n = triangle.normal_at(point!(-0.2, 0.3, 0), &xs[0]);
+            assert!((n.x + 0.5547).abs() < 1e-4);
+            assert!((n.y - 0.83205).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn negative_indices_are_relative() {
+        let input = "v 0 1 0\nv -1 0 0\nv 1 0 0\nf -3 -2 -1";
+        let obj = parse_obj_file(input).unwrap();
+        let triangle = &obj.default_group.triangles[0];
+        let expected = Triangle::new(point!(0, 1, 0), point!(-1, 0, 0), point!(1, 0, 0));
+        assert!(triangle.bounds() == expected.bounds());
+        let hit = Intersection::new(0.0, triangle.as_ref());
+        assert!(triangle.normal_at(point!(0, 0.5, 0), &hit) == vector!(0, 0, -1));
+    }
+
+    #[test]
+    fn reject_invalid_indices() {
+        let input = "v 0 1 0\nv -1 0 0\nv 1 0 0\nf 1 2 4";
+        let err = parse_obj_file(input).unwrap_err();
+        assert_eq!(
+            err,
+            ObjError::InvalidIndex {
+                line: 4,
+                index: "4".to_string()
+            }
+        );
+
+        for face in ["f 0 1 2", "f 1 2 a", "f 1 2 -4", "f 1//1 2//1 3//1"] {
+            let input = format!("v 0 1 0\nv -1 0 0\nv 1 0 0\n{}", face);
+            assert!(matches!(
+                parse_obj_file(&input),
+                Err(ObjError::InvalidIndex { line: 4, .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn reject_malformed_lines() {
+        for input in [
+            "v 1 2",
+            "v 1 2 x",
+            "v 1 2 3 4 5",
+            "vn 1",
+            "vt",
+            "vt 1 2 3 4",
+            "v 0 1 0\nv 1 0 0\nf 1 2",
+        ] {
+            assert!(matches!(
+                parse_obj_file(input),
+                Err(ObjError::MalformedLine { .. })
+            ));
+        }
+        let err = parse_obj_file("v 1 2").unwrap_err();
+        assert_eq!(err.to_string(), "line 1: malformed statement `v 1 2`");
+    }
+
+    #[test]
+    fn convert_to_group() {
+        let input = "v -1 1 0\nv -1 0 0\nv 1 0 0\nv 1 1 0\n\
+                     f 1 2 3\n\
+                     g FirstGroup\nf 1 2 3\n\
+                     g SecondGroup\nf 1 3 4";
+        let group = parse_obj_file(input).unwrap().into_group();
+        assert_eq!(group.children().len(), 3);
+
+        let r = Ray::new(point!(-0.5, 0.5, -1), vector!(0, 0, 1));
+        assert_eq!(group.intersect(&r).len(), 2);
+    }
+
+    #[test]
+    fn empty_groups_are_left_out() {
+        let input = "v -1 1 0\nv -1 0 0\nv 1 0 0\n\
+                     g a\ng b\nf 1 2 3\ng c";
+        let group = parse_obj_file(input).unwrap().into_group();
+        assert_eq!(group.children().len(), 1);
+        let bounds = group.bounds();
+        assert!(bounds.min == point!(-1, 0, 0));
+        assert!(bounds.max == point!(1, 1, 0));
+    }
+
+    #[test]
+    fn convert_to_bvh() {
+        let input = "v 0 0 0\nv 1 0 0\nv 0 1 0\nv 10 0 0\nv 11 0 0\nv 10 1 0\n\
+                     f 1 2 3\n\
+                     g FirstGroup\nf 4 5 6\n\
+                     g SecondGroup\nf 1 2 3";
+        let bvh = parse_obj_file(input).unwrap().into_bvh(1);
+        assert_eq!(bvh.children().count(), 3);
+        // The two identical triangles can't be told apart
+        let stats = bvh.stats();
+        assert_eq!(stats.leaf_count, 2);
+        assert_eq!(stats.average_leaf_size, 1.5);
+    }
+}
@@ -0,0 +1,74 @@
+use crate::color::Color;
+use crate::patterns::{Pattern, PatternData, PatternPair};
+use crate::tuple::Point;
+use std::rc::Rc;
+
+/// The average of two patterns
+///
+/// Blending two perpendicular `Stripe` patterns gives a plaid look.
+///
+/// # Examples
+/// ```
+/// use ray_tracer::color::Color;
+/// use ray_tracer::patterns::{Blend, Pattern, Solid};
+/// use ray_tracer::tuple::{Point, Tuple};
+/// use ray_tracer::{color, point};
+/// use std::rc::Rc;
+///
+/// let red = Rc::new(Solid::new(color!(1, 0, 0)));
+/// let blue = Rc::new(Solid::new(color!(0, 0, 1)));
+/// let p = Blend::new(red, blue);
+/// assert!(p.pattern_at(point!(0, 0, 0)) == color!(0.5, 0, 0.5));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Blend {
+    data: PatternData,
+    pub pair: PatternPair,
+}
+
+impl Blend {
+    pub fn new(a: Rc<dyn Pattern>, b: Rc<dyn Pattern>) -> Blend {
+        Blend {
+            data: PatternData::new(),
+            pair: PatternPair::new(a, b),
+        }
+    }
+}
+
+impl Pattern for Blend {
+    fn data(&self) -> &PatternData {
+        &self.data
+    }
+
+    fn data_mut(&mut self) -> &mut PatternData {
+        &mut self.data
+    }
+
+    fn pattern_at(&self, point: Point) -> Color {
+        (self.pair.a_at(point) + self.pair.b_at(point)) * 0.5
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color;
+    use crate::patterns::Stripe;
+    use crate::point;
+    use crate::transform::rotation_y;
+    use crate::tuple::Tuple;
+    use std::f64::consts::FRAC_PI_2;
+
+    #[test]
+    fn blend_perpendicular_stripes() {
+        let along_x = Stripe::new(color!(1, 1, 1), color!(0, 0, 0));
+        let mut along_z = Stripe::new(color!(1, 1, 1), color!(0, 0, 0));
+        along_z.set_transform(rotation_y(FRAC_PI_2));
+        let p = Blend::new(Rc::new(along_x), Rc::new(along_z));
+
+        // Both white, both black, and one of each
+        assert!(p.pattern_at(point!(0.5, 0, -0.5)) == color!(1, 1, 1));
+        assert!(p.pattern_at(point!(1.5, 0, 0.5)) == color!(0, 0, 0));
+        assert!(p.pattern_at(point!(0.5, 0, 0.5)) == color!(0.5, 0.5, 0.5));
+    }
+}
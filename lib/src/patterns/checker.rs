@@ -0,0 +1,121 @@
+use crate::color::Color;
+use crate::patterns::{Pattern, PatternData, PatternPair};
+use crate::tuple::Point;
+
+/// Unit cubes alternating between two colors in all three dimensions
+///
+/// # Examples
+/// ```
+/// use ray_tracer::color::Color;
+/// use ray_tracer::patterns::{Checker, Pattern};
+/// use ray_tracer::tuple::{Point, Tuple};
+/// use ray_tracer::{color, point};
+///
+/// let p = Checker::new(color!(1, 1, 1), color!(0, 0, 0));
+/// assert!(p.pattern_at(point!(0.5, 0.5, 0.5)) == color!(1, 1, 1));
+/// assert!(p.pattern_at(point!(1.5, 0.5, 0.5)) == color!(0, 0, 0));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Checker {
+    data: PatternData,
+    pub pair: PatternPair,
+}
+
+impl Checker {
+    /// Unit cubes of the colors `a` and `b`, with `a` at the origin
+    pub fn new(a: Color, b: Color) -> Checker {
+        Checker::nested(PatternPair::solid(a, b))
+    }
+
+    /// A three dimensional checkerboard that alternates between the two
+    /// patterns of `pair`
+    pub fn nested(pair: PatternPair) -> Checker {
+        Checker {
+            data: PatternData::new(),
+            pair,
+        }
+    }
+}
+
+impl Pattern for Checker {
+    fn data(&self) -> &PatternData {
+        &self.data
+    }
+
+    fn data_mut(&mut self) -> &mut PatternData {
+        &mut self.data
+    }
+
+    fn pattern_at(&self, point: Point) -> Color {
+        let sum = point.x.floor() + point.y.floor() + point.z.floor();
+        if sum.rem_euclid(2.0) == 0.0 {
+            self.pair.a_at(point)
+        } else {
+            self.pair.b_at(point)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color;
+    use crate::patterns::Stripe;
+    use crate::point;
+    use crate::shapes::Sphere;
+    use crate::transform::{rotation_y, scaling};
+    use crate::tuple::Tuple;
+    use std::f64::consts::FRAC_PI_2;
+    use std::rc::Rc;
+
+    fn checker() -> Checker {
+        Checker::new(color!(1, 1, 1), color!(0, 0, 0))
+    }
+
+    #[test]
+    fn checkers_repeat_in_x() {
+        let p = checker();
+        assert!(p.pattern_at(point!(0, 0, 0)) == color!(1, 1, 1));
+        assert!(p.pattern_at(point!(0.99, 0, 0)) == color!(1, 1, 1));
+        assert!(p.pattern_at(point!(1.01, 0, 0)) == color!(0, 0, 0));
+    }
+
+    #[test]
+    fn checkers_repeat_in_y() {
+        let p = checker();
+        assert!(p.pattern_at(point!(0, 0.99, 0)) == color!(1, 1, 1));
+        assert!(p.pattern_at(point!(0, 1.01, 0)) == color!(0, 0, 0));
+    }
+
+    #[test]
+    fn checkers_repeat_in_z() {
+        let p = checker();
+        assert!(p.pattern_at(point!(0, 0, 0.99)) == color!(1, 1, 1));
+        assert!(p.pattern_at(point!(0, 0, 1.01)) == color!(0, 0, 0));
+    }
+
+    #[test]
+    fn checkers_below_the_origin() {
+        let p = checker();
+        assert!(p.pattern_at(point!(-0.5, 0.5, 0.5)) == color!(0, 0, 0));
+        assert!(p.pattern_at(point!(-0.5, -0.5, 0.5)) == color!(1, 1, 1));
+    }
+
+    #[test]
+    fn checkers_of_stripes() {
+        // Stripes along x in the white squares, and along z in the
+        // black ones, one world unit wide inside squares of four units
+        let mut along_x = Stripe::new(color!(1, 0, 0), color!(0, 1, 0));
+        along_x.set_transform(scaling(0.25, 0.25, 0.25));
+        let mut along_z = Stripe::new(color!(0, 0, 1), color!(0, 0, 0));
+        along_z.set_transform(rotation_y(FRAC_PI_2) * scaling(0.25, 0.25, 0.25));
+        let mut p = Checker::nested(PatternPair::new(Rc::new(along_x), Rc::new(along_z)));
+        p.set_transform(scaling(4.0, 4.0, 4.0));
+
+        let s = Sphere::new();
+        assert!(p.pattern_at_shape(&s, point!(0.5, 0, 0.5)) == color!(1, 0, 0));
+        assert!(p.pattern_at_shape(&s, point!(1.5, 0, 0.5)) == color!(0, 1, 0));
+        assert!(p.pattern_at_shape(&s, point!(4.5, 0, 0.5)) == color!(0, 0, 0));
+        assert!(p.pattern_at_shape(&s, point!(4.5, 0, 1.5)) == color!(0, 0, 1));
+    }
+}
@@ -0,0 +1,78 @@
+use crate::color::Color;
+use crate::patterns::{Pattern, PatternData, PatternPair};
+use crate::tuple::Point;
+
+/// A linear blend from `a` to `b` along the x axis, repeated every unit
+///
+/// # Examples
+/// ```
+/// use ray_tracer::color::Color;
+/// use ray_tracer::patterns::{Gradient, Pattern};
+/// use ray_tracer::tuple::{Point, Tuple};
+/// use ray_tracer::{color, point};
+///
+/// let p = Gradient::new(color!(1, 1, 1), color!(0, 0, 0));
+/// assert!(p.pattern_at(point!(0.5, 0, 0)) == color!(0.5, 0.5, 0.5));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Gradient {
+    data: PatternData,
+    pub pair: PatternPair,
+}
+
+impl Gradient {
+    /// A gradient from the color `a` at x = 0 to `b` at x = 1
+    pub fn new(a: Color, b: Color) -> Gradient {
+        Gradient::nested(PatternPair::solid(a, b))
+    }
+
+    /// A gradient that blends the two patterns of `pair`, instead of two
+    /// colors
+    pub fn nested(pair: PatternPair) -> Gradient {
+        Gradient {
+            data: PatternData::new(),
+            pair,
+        }
+    }
+}
+
+impl Pattern for Gradient {
+    fn data(&self) -> &PatternData {
+        &self.data
+    }
+
+    fn data_mut(&mut self) -> &mut PatternData {
+        &mut self.data
+    }
+
+    fn pattern_at(&self, point: Point) -> Color {
+        let a = self.pair.a_at(point);
+        let b = self.pair.b_at(point);
+        let fraction = point.x - point.x.floor();
+        a + (b - a) * fraction
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color;
+    use crate::point;
+    use crate::tuple::Tuple;
+
+    #[test]
+    fn gradient_interpolates_between_colors() {
+        let p = Gradient::new(color!(1, 1, 1), color!(0, 0, 0));
+        assert!(p.pattern_at(point!(0, 0, 0)) == color!(1, 1, 1));
+        assert!(p.pattern_at(point!(0.25, 0, 0)) == color!(0.75, 0.75, 0.75));
+        assert!(p.pattern_at(point!(0.5, 0, 0)) == color!(0.5, 0.5, 0.5));
+        assert!(p.pattern_at(point!(0.75, 0, 0)) == color!(0.25, 0.25, 0.25));
+    }
+
+    #[test]
+    fn gradient_repeats_every_unit() {
+        let p = Gradient::new(color!(1, 1, 1), color!(0, 0, 0));
+        assert!(p.pattern_at(point!(1.25, 0, 0)) == color!(0.75, 0.75, 0.75));
+        assert!(p.pattern_at(point!(-0.75, 0, 0)) == color!(0.75, 0.75, 0.75));
+    }
+}
@@ -0,0 +1,208 @@
+// Exports
+pub mod blend;
+pub mod checker;
+pub mod gradient;
+pub mod perturbed;
+pub mod ring;
+pub mod solid;
+pub mod stripe;
+pub mod texture_map;
+pub mod uv_mapping;
+
+// Imports
+use crate::color::Color;
+use crate::shapes::Shape;
+use crate::tuple::{Matrix4, Point};
+pub use blend::Blend;
+pub use checker::Checker;
+pub use gradient::Gradient;
+pub use perturbed::Perturbed;
+pub use ring::Ring;
+pub use solid::Solid;
+use std::fmt::Debug;
+use std::rc::Rc;
+pub use stripe::Stripe;
+pub use texture_map::{TextureFilter, TextureMap};
+pub use uv_mapping::UvMapping;
+
+/// The state that every `Pattern` carries
+#[derive(Clone, Debug)]
+pub struct PatternData {
+    transform: Matrix4,
+    // Cached so that it is not computed for every lookup
+    inverse: Matrix4,
+}
+
+impl PatternData {
+    pub fn new() -> PatternData {
+        PatternData {
+            transform: Matrix4::identity(),
+            inverse: Matrix4::identity(),
+        }
+    }
+
+    /// Change the transformation, caching its inverse
+    ///
+    /// # Panics
+    /// If `transform` is not invertible.
+    pub fn set_transform(&mut self, transform: Matrix4) {
+        self.inverse = transform
+            .inverse()
+            .expect("the transformation of a pattern must be invertible");
+        self.transform = transform;
+    }
+}
+
+impl Default for PatternData {
+    fn default() -> Self {
+        PatternData::new()
+    }
+}
+
+/// The `Pattern` trait is implemented by everything that can color a
+/// surface point by point.
+///
+/// Implementors only compute the `Color` at a `Point` in pattern space,
+/// through `pattern_at`; the pattern is positioned relative to the
+/// object it is applied to by its own transformation.
+pub trait Pattern: Debug {
+    fn data(&self) -> &PatternData;
+    fn data_mut(&mut self) -> &mut PatternData;
+
+    /// The `Color` at a `Point` that is already in pattern space
+    fn pattern_at(&self, point: Point) -> Color;
+
+    fn transform(&self) -> &Matrix4 {
+        &self.data().transform
+    }
+
+    fn inverse(&self) -> &Matrix4 {
+        &self.data().inverse
+    }
+
+    /// Change the transformation of the pattern, relative to the
+    /// object it is applied to
+    ///
+    /// # Panics
+    /// If `transform` is not invertible.
+    fn set_transform(&mut self, transform: Matrix4) {
+        self.data_mut().set_transform(transform);
+    }
+
+    /// The `Color` at a `Point` in the space of the pattern that
+    /// contains this one, for patterns made of other patterns
+    fn pattern_at_parent(&self, point: Point) -> Color {
+        self.pattern_at(*self.inverse() * point)
+    }
+
+    /// The `Color` at the world space `point` on `object`, going
+    /// through object space and then pattern space
+    fn pattern_at_shape(&self, object: &dyn Shape, point: Point) -> Color {
+        self.pattern_at_parent(object.world_to_object(point))
+    }
+}
+
+/// The two patterns combined by a `Stripe`, `Gradient`, `Ring`,
+/// `Checker` or `Blend`
+///
+/// Both are positioned relative to the pattern that holds the pair, so
+/// a rotated `Stripe` nested in a `Checker` is rotated in every square.
+#[derive(Clone, Debug)]
+pub struct PatternPair {
+    pub a: Rc<dyn Pattern>,
+    pub b: Rc<dyn Pattern>,
+}
+
+impl PatternPair {
+    pub fn new(a: Rc<dyn Pattern>, b: Rc<dyn Pattern>) -> PatternPair {
+        PatternPair { a, b }
+    }
+
+    /// A pair of `Solid` patterns, for the plain colors `a` and `b`
+    pub fn solid(a: Color, b: Color) -> PatternPair {
+        PatternPair::new(Rc::new(Solid::new(a)), Rc::new(Solid::new(b)))
+    }
+
+    /// The `Color` of `a` at `point`, in the space of the pattern that
+    /// holds the pair
+    pub fn a_at(&self, point: Point) -> Color {
+        self.a.pattern_at_parent(point)
+    }
+
+    /// The `Color` of `b` at `point`, in the space of the pattern that
+    /// holds the pair
+    pub fn b_at(&self, point: Point) -> Color {
+        self.b.pattern_at_parent(point)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::color;
+    use crate::point;
+    use crate::shapes::Sphere;
+    use crate::transform::{scaling, translation};
+    use crate::tuple::Tuple;
+
+    /// A `Pattern` that returns the pattern space `Point` as a `Color`
+    #[derive(Debug, Default)]
+    pub(crate) struct TestPattern {
+        data: PatternData,
+    }
+
+    impl Pattern for TestPattern {
+        fn data(&self) -> &PatternData {
+            &self.data
+        }
+
+        fn data_mut(&mut self) -> &mut PatternData {
+            &mut self.data
+        }
+
+        fn pattern_at(&self, point: Point) -> Color {
+            color!(point.x, point.y, point.z)
+        }
+    }
+
+    #[test]
+    fn default_pattern_transformation() {
+        let p = TestPattern::default();
+        assert!(*p.transform() == Matrix4::identity());
+    }
+
+    #[test]
+    fn assign_pattern_transformation() {
+        let mut p = TestPattern::default();
+        p.set_transform(translation(1.0, 2.0, 3.0));
+        assert!(*p.transform() == translation(1.0, 2.0, 3.0));
+    }
+
+    #[test]
+    fn pattern_with_object_transformation() {
+        let mut s = Sphere::new();
+        s.set_transform(scaling(2.0, 2.0, 2.0));
+        let p = TestPattern::default();
+        let c = p.pattern_at_shape(&s, point!(2, 3, 4));
+        assert!(c == color!(1, 1.5, 2));
+    }
+
+    #[test]
+    fn pattern_with_pattern_transformation() {
+        let s = Sphere::new();
+        let mut p = TestPattern::default();
+        p.set_transform(scaling(2.0, 2.0, 2.0));
+        let c = p.pattern_at_shape(&s, point!(2, 3, 4));
+        assert!(c == color!(1, 1.5, 2));
+    }
+
+    #[test]
+    fn pattern_with_object_and_pattern_transformation() {
+        let mut s = Sphere::new();
+        s.set_transform(scaling(2.0, 2.0, 2.0));
+        let mut p = TestPattern::default();
+        p.set_transform(translation(0.5, 1.0, 1.5));
+        let c = p.pattern_at_shape(&s, point!(2.5, 3, 3.5));
+        assert!(c == color!(0.75, 0.5, 0.25));
+    }
+}
@@ -0,0 +1,122 @@
+use crate::color::Color;
+use crate::noise::Noise;
+use crate::patterns::{Pattern, PatternData};
+use crate::tuple::{Point, Tuple, Vector};
+use std::rc::Rc;
+
+/// Another pattern, looked up at a point jittered by Perlin noise
+///
+/// Perturbing a `Ring` or a `Stripe` gives wood and marble looking
+/// surfaces.
+///
+/// # Examples
+/// ```
+/// use ray_tracer::color::Color;
+/// use ray_tracer::patterns::{Pattern, Perturbed, Stripe};
+/// use ray_tracer::tuple::{Point, Tuple};
+/// use ray_tracer::{color, point};
+/// use std::rc::Rc;
+///
+/// let stripes = Rc::new(Stripe::new(color!(1, 1, 1), color!(0, 0, 0)));
+/// let marble = Perturbed::new(stripes, 0.4);
+/// // Away from the edges of the stripes, the jitter is not visible
+/// assert!(marble.pattern_at(point!(0.5, 0.3, 0.2)) == color!(1, 1, 1));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Perturbed {
+    data: PatternData,
+    pub pattern: Rc<dyn Pattern>,
+    /// How far the lookup point can move, along each axis
+    pub scale: f64,
+    pub noise: Noise,
+}
+
+impl Perturbed {
+    pub fn new(pattern: Rc<dyn Pattern>, scale: f64) -> Perturbed {
+        Perturbed {
+            data: PatternData::new(),
+            pattern,
+            scale,
+            noise: Noise::default(),
+        }
+    }
+}
+
+impl Pattern for Perturbed {
+    fn data(&self) -> &PatternData {
+        &self.data
+    }
+
+    fn data_mut(&mut self) -> &mut PatternData {
+        &mut self.data
+    }
+
+    fn pattern_at(&self, point: Point) -> Color {
+        // Sample the noise at far apart points, so that the three
+        // components are not correlated
+        let jitter = Vector::new(
+            self.noise.perlin(point),
+            self.noise.perlin(point + Vector::new(31.7, 0.0, 0.0)),
+            self.noise.perlin(point + Vector::new(0.0, 0.0, 57.3)),
+        );
+
+        self.pattern.pattern_at_parent(point + jitter * self.scale)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color;
+    use crate::patterns::tests::TestPattern;
+    use crate::patterns::Stripe;
+    use crate::point;
+    use crate::tuple::Tuple;
+
+    #[test]
+    fn perturbation_is_bounded_by_scale() {
+        let p = Perturbed::new(Rc::new(TestPattern::default()), 0.25);
+        for i in 0..100 {
+            let t = i as f64 * 0.173;
+            let point = point!(t, 1.0 - t, t * 0.5);
+            let c = p.pattern_at(point);
+            assert!((c.red - point.x).abs() <= 0.25);
+            assert!((c.green - point.y).abs() <= 0.25);
+            assert!((c.blue - point.z).abs() <= 0.25);
+        }
+    }
+
+    #[test]
+    fn perturbation_is_deterministic() {
+        let stripes = Rc::new(Stripe::new(color!(1, 1, 1), color!(0, 0, 0)));
+        let p = Perturbed::new(stripes, 1.0);
+        let point = point!(3.3, 1.7, -0.4);
+        assert!(p.pattern_at(point) == p.pattern_at(point));
+    }
+
+    #[test]
+    fn perturbation_depends_on_the_noise_seed() {
+        let p1 = Perturbed::new(Rc::new(TestPattern::default()), 0.5);
+        let mut p2 = p1.clone();
+        p2.noise = Noise::new(99);
+        let point = point!(3.3, 1.7, -0.4);
+        assert!(p1.pattern_at(point) != p2.pattern_at(point));
+    }
+
+    #[test]
+    fn zero_scale_does_not_perturb() {
+        let p = Perturbed::new(Rc::new(TestPattern::default()), 0.0);
+        assert!(p.pattern_at(point!(0.3, 0.6, 0.9)) == color!(0.3, 0.6, 0.9));
+    }
+
+    #[test]
+    fn perturbation_moves_stripe_edges() {
+        let stripes: Rc<dyn Pattern> = Rc::new(Stripe::new(color!(1, 1, 1), color!(0, 0, 0)));
+        let p = Perturbed::new(Rc::clone(&stripes), 1.0);
+        let changed = (0..100)
+            .map(|i| point!(i as f64 * 0.31, 0.5, i as f64 * 0.17))
+            .filter(|&point| p.pattern_at(point) != stripes.pattern_at(point))
+            .count();
+        assert!(changed > 0);
+    }
+}
@@ -0,0 +1,77 @@
+use crate::color::Color;
+use crate::patterns::{Pattern, PatternData, PatternPair};
+use crate::tuple::Point;
+
+/// Concentric rings around the y axis, alternating between two colors
+/// every unit of distance
+///
+/// # Examples
+/// ```
+/// use ray_tracer::color::Color;
+/// use ray_tracer::patterns::{Pattern, Ring};
+/// use ray_tracer::tuple::{Point, Tuple};
+/// use ray_tracer::{color, point};
+///
+/// let p = Ring::new(color!(1, 1, 1), color!(0, 0, 0));
+/// assert!(p.pattern_at(point!(0, 0, 0)) == color!(1, 1, 1));
+/// assert!(p.pattern_at(point!(1, 0, 0)) == color!(0, 0, 0));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Ring {
+    data: PatternData,
+    pub pair: PatternPair,
+}
+
+impl Ring {
+    /// Rings of the colors `a` and `b` around the y axis, starting with
+    /// `a` at the center
+    pub fn new(a: Color, b: Color) -> Ring {
+        Ring::nested(PatternPair::solid(a, b))
+    }
+
+    /// Rings that alternate between the two patterns of `pair`
+    pub fn nested(pair: PatternPair) -> Ring {
+        Ring {
+            data: PatternData::new(),
+            pair,
+        }
+    }
+}
+
+impl Pattern for Ring {
+    fn data(&self) -> &PatternData {
+        &self.data
+    }
+
+    fn data_mut(&mut self) -> &mut PatternData {
+        &mut self.data
+    }
+
+    fn pattern_at(&self, point: Point) -> Color {
+        let distance = point.x.hypot(point.z);
+        if distance.floor().rem_euclid(2.0) == 0.0 {
+            self.pair.a_at(point)
+        } else {
+            self.pair.b_at(point)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color;
+    use crate::point;
+    use crate::tuple::Tuple;
+
+    #[test]
+    fn ring_extends_in_x_and_z() {
+        let p = Ring::new(color!(1, 1, 1), color!(0, 0, 0));
+        assert!(p.pattern_at(point!(0, 0, 0)) == color!(1, 1, 1));
+        assert!(p.pattern_at(point!(1, 0, 0)) == color!(0, 0, 0));
+        assert!(p.pattern_at(point!(0, 0, 1)) == color!(0, 0, 0));
+        // Just slightly more than sqrt(2) / 2 in both x and z
+        assert!(p.pattern_at(point!(0.708, 0, 0.708)) == color!(0, 0, 0));
+        assert!(p.pattern_at(point!(0, 5, 2.5)) == color!(1, 1, 1));
+    }
+}
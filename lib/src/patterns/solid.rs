@@ -0,0 +1,44 @@
+use crate::color::Color;
+use crate::patterns::{Pattern, PatternData};
+use crate::tuple::Point;
+
+/// A single `Color` everywhere, mostly useful inside other patterns
+///
+/// # Examples
+/// ```
+/// use ray_tracer::color::Color;
+/// use ray_tracer::patterns::{Pattern, Solid};
+/// use ray_tracer::tuple::{Point, Tuple};
+/// use ray_tracer::{color, point};
+///
+/// let p = Solid::new(color!(1, 0.5, 0));
+/// assert!(p.pattern_at(point!(12, -3, 7)) == color!(1, 0.5, 0));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Solid {
+    data: PatternData,
+    pub color: Color,
+}
+
+impl Solid {
+    pub fn new(color: Color) -> Solid {
+        Solid {
+            data: PatternData::new(),
+            color,
+        }
+    }
+}
+
+impl Pattern for Solid {
+    fn data(&self) -> &PatternData {
+        &self.data
+    }
+
+    fn data_mut(&mut self) -> &mut PatternData {
+        &mut self.data
+    }
+
+    fn pattern_at(&self, _point: Point) -> Color {
+        self.color
+    }
+}
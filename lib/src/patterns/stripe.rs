@@ -0,0 +1,128 @@
+use crate::color::Color;
+use crate::patterns::{Pattern, PatternData, PatternPair};
+use crate::tuple::Point;
+
+/// Stripes that alternate between two colors along the x axis, each
+/// one unit wide
+///
+/// # Examples
+/// ```
+/// use ray_tracer::color::Color;
+/// use ray_tracer::patterns::{Pattern, Stripe};
+/// use ray_tracer::tuple::{Point, Tuple};
+/// use ray_tracer::{color, point};
+///
+/// let p = Stripe::new(color!(1, 1, 1), color!(0, 0, 0));
+/// assert!(p.pattern_at(point!(0.5, 0, 0)) == color!(1, 1, 1));
+/// assert!(p.pattern_at(point!(1.5, 0, 0)) == color!(0, 0, 0));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Stripe {
+    data: PatternData,
+    pub pair: PatternPair,
+}
+
+impl Stripe {
+    /// Stripes of the colors `a` and `b`, starting with `a` at x = 0
+    pub fn new(a: Color, b: Color) -> Stripe {
+        Stripe::nested(PatternPair::solid(a, b))
+    }
+
+    /// Stripes that alternate between the two patterns of `pair`
+    pub fn nested(pair: PatternPair) -> Stripe {
+        Stripe {
+            data: PatternData::new(),
+            pair,
+        }
+    }
+}
+
+impl Pattern for Stripe {
+    fn data(&self) -> &PatternData {
+        &self.data
+    }
+
+    fn data_mut(&mut self) -> &mut PatternData {
+        &mut self.data
+    }
+
+    fn pattern_at(&self, point: Point) -> Color {
+        if point.x.floor().rem_euclid(2.0) == 0.0 {
+            self.pair.a_at(point)
+        } else {
+            self.pair.b_at(point)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color;
+    use crate::point;
+    use crate::shapes::{Shape, Sphere};
+    use crate::transform::{scaling, translation};
+    use crate::tuple::Tuple;
+
+    fn white() -> Color {
+        color!(1, 1, 1)
+    }
+
+    fn black() -> Color {
+        color!(0, 0, 0)
+    }
+
+    #[test]
+    fn create_stripe_pattern() {
+        let p = Stripe::new(white(), black());
+        assert!(p.pair.a.pattern_at(point!(0, 0, 0)) == white());
+        assert!(p.pair.b.pattern_at(point!(0, 0, 0)) == black());
+    }
+
+    #[test]
+    fn stripe_is_constant_in_y_and_z() {
+        let p = Stripe::new(white(), black());
+        for point in [point!(0, 0, 0), point!(0, 1, 0), point!(0, 2, 0)] {
+            assert!(p.pattern_at(point) == white());
+        }
+        for point in [point!(0, 0, 1), point!(0, 0, 2)] {
+            assert!(p.pattern_at(point) == white());
+        }
+    }
+
+    #[test]
+    fn stripe_alternates_in_x() {
+        let p = Stripe::new(white(), black());
+        assert!(p.pattern_at(point!(0, 0, 0)) == white());
+        assert!(p.pattern_at(point!(0.9, 0, 0)) == white());
+        assert!(p.pattern_at(point!(1, 0, 0)) == black());
+        assert!(p.pattern_at(point!(-0.1, 0, 0)) == black());
+        assert!(p.pattern_at(point!(-1, 0, 0)) == black());
+        assert!(p.pattern_at(point!(-1.1, 0, 0)) == white());
+    }
+
+    #[test]
+    fn stripes_with_object_transformation() {
+        let mut s = Sphere::new();
+        s.set_transform(scaling(2.0, 2.0, 2.0));
+        let p = Stripe::new(white(), black());
+        assert!(p.pattern_at_shape(&s, point!(1.5, 0, 0)) == white());
+    }
+
+    #[test]
+    fn stripes_with_pattern_transformation() {
+        let s = Sphere::new();
+        let mut p = Stripe::new(white(), black());
+        p.set_transform(scaling(2.0, 2.0, 2.0));
+        assert!(p.pattern_at_shape(&s, point!(1.5, 0, 0)) == white());
+    }
+
+    #[test]
+    fn stripes_with_object_and_pattern_transformation() {
+        let mut s = Sphere::new();
+        s.set_transform(scaling(2.0, 2.0, 2.0));
+        let mut p = Stripe::new(white(), black());
+        p.set_transform(translation(0.5, 0.0, 0.0));
+        assert!(p.pattern_at_shape(&s, point!(2.5, 0, 0)) == white());
+    }
+}
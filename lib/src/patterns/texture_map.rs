@@ -0,0 +1,230 @@
+use crate::canvas::Canvas;
+use crate::color; // for the macro
+use crate::color::Color;
+use crate::patterns::uv_mapping::{cube_map, UvMapping};
+use crate::patterns::{Pattern, PatternData};
+use crate::tuple::Point;
+use std::ops::Range;
+use std::rc::Rc;
+
+/// How a `TextureMap` reads colors between the pixels of its image
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TextureFilter {
+    /// The color of the closest pixel, which looks blocky up close
+    Nearest,
+    /// A weighted average of the four closest pixels
+    Bilinear,
+}
+
+/// An image wrapped around a shape with a `UvMapping`
+///
+/// The image is shared, so that many patterns can use a large texture
+/// without copying it. The top row of the image is at `v = 1.0`.
+///
+/// # Examples
+/// ```
+/// use ray_tracer::canvas::Canvas;
+/// use ray_tracer::color::Color;
+/// use ray_tracer::patterns::{Pattern, TextureMap, UvMapping};
+/// use ray_tracer::tuple::{Point, Tuple};
+/// use ray_tracer::{color, point};
+/// use std::rc::Rc;
+///
+/// let ppm = "P3\n2 1\n255\n255 0 0  0 0 255\n";
+/// let image = Canvas::from_ppm(ppm.as_bytes()).unwrap();
+/// let p = TextureMap::new(Rc::new(image), UvMapping::Planar);
+/// assert!(p.pattern_at(point!(0.2, 0, 0.5)) == color!(1, 0, 0));
+/// assert!(p.pattern_at(point!(0.8, 0, 0.5)) == color!(0, 0, 1));
+/// ```
+#[derive(Clone, Debug)]
+pub struct TextureMap {
+    data: PatternData,
+    pub image: Rc<Canvas>,
+    pub mapping: UvMapping,
+    pub filter: TextureFilter,
+}
+
+impl TextureMap {
+    /// Create a `TextureMap` that uses the `Nearest` filter
+    pub fn new(image: Rc<Canvas>, mapping: UvMapping) -> TextureMap {
+        TextureMap {
+            data: PatternData::new(),
+            image,
+            mapping,
+            filter: TextureFilter::Nearest,
+        }
+    }
+
+    /// The color of the image at `(u, v)`, both between 0.0 and 1.0
+    ///
+    /// An empty image is black everywhere.
+    pub fn uv_pattern_at(&self, u: f64, v: f64) -> Color {
+        let (width, height) = (self.image.width, self.image.height);
+        self.sample(u, v, 0..width, 0..height)
+    }
+
+    /// The color at `(u, v)` inside of the pixels `columns` and `rows` of
+    /// the image, which are never blended with the pixels around them
+    ///
+    /// Pixels are sampled at their centers, so that every pixel covers
+    /// the same share of `(u, v)`.
+    fn sample(&self, u: f64, v: f64, columns: Range<usize>, rows: Range<usize>) -> Color {
+        if columns.is_empty() || rows.is_empty() {
+            return color!(0, 0, 0);
+        }
+        let (width, height) = (columns.len() as f64, rows.len() as f64);
+        // Flip v, since the rows of the image go downwards
+        let x = (u.clamp(0.0, 1.0) * width - 0.5).clamp(0.0, width - 1.0);
+        let y = ((1.0 - v.clamp(0.0, 1.0)) * height - 0.5).clamp(0.0, height - 1.0);
+        let pixel = |x: usize, y: usize| self.image.pixel_at(columns.start + x, rows.start + y);
+
+        match self.filter {
+            TextureFilter::Nearest => pixel(x.round() as usize, y.round() as usize),
+            TextureFilter::Bilinear => {
+                let (x0, y0) = (x.floor() as usize, y.floor() as usize);
+                let (x1, y1) = (
+                    (x0 + 1).min(columns.len() - 1),
+                    (y0 + 1).min(rows.len() - 1),
+                );
+                let (tx, ty) = (x - x0 as f64, y - y0 as f64);
+
+                let top = pixel(x0, y0) * (1.0 - tx) + pixel(x1, y0) * tx;
+                let bottom = pixel(x0, y1) * (1.0 - tx) + pixel(x1, y1) * tx;
+                top * (1.0 - ty) + bottom * ty
+            }
+        }
+    }
+}
+
+impl Pattern for TextureMap {
+    fn data(&self) -> &PatternData {
+        &self.data
+    }
+
+    fn data_mut(&mut self) -> &mut PatternData {
+        &mut self.data
+    }
+
+    fn pattern_at(&self, point: Point) -> Color {
+        if self.mapping == UvMapping::Cube {
+            // Keep to the face's cell of the cross, so that the edges of
+            // a face don't pick up the colors of the faces next to it
+            let (face, u, v) = cube_map(point);
+            let (column, row) = face.cross_position();
+            let (width, height) = (self.image.width / 4, self.image.height / 3);
+            // Rows of the cross count from the bottom, those of the image
+            // from the top
+            let row = 2 - row;
+            return self.sample(
+                u,
+                v,
+                column * width..(column + 1) * width,
+                row * height..(row + 1) * height,
+            );
+        }
+
+        let (u, v) = self.mapping.map(point);
+        self.uv_pattern_at(u, v)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::point;
+    use crate::shapes::{Shape, Sphere};
+    use crate::transform::scaling;
+    use crate::tuple::Tuple;
+
+    /// A 2 by 2 image, red and green on top, blue and white below
+    fn quadrants() -> Rc<Canvas> {
+        let ppm = "P3\n2 2\n255\n255 0 0  0 255 0\n0 0 255  255 255 255\n";
+        Rc::new(Canvas::from_ppm(ppm.as_bytes()).unwrap())
+    }
+
+    #[test]
+    fn empty_image_is_black() {
+        let image = Canvas::new(0, 0);
+        let mut p = TextureMap::new(Rc::new(image), UvMapping::Planar);
+        assert!(p.uv_pattern_at(0.5, 0.5) == color!(0, 0, 0));
+        p.filter = TextureFilter::Bilinear;
+        assert!(p.pattern_at(point!(0.5, 0, 0.5)) == color!(0, 0, 0));
+    }
+
+    #[test]
+    fn nearest_filter_picks_closest_pixel() {
+        let p = TextureMap::new(quadrants(), UvMapping::Planar);
+        assert!(p.uv_pattern_at(0.0, 1.0) == color!(1, 0, 0));
+        assert!(p.uv_pattern_at(0.9, 0.6) == color!(0, 1, 0));
+        assert!(p.uv_pattern_at(0.1, 0.4) == color!(0, 0, 1));
+        assert!(p.uv_pattern_at(1.0, 0.0) == color!(1, 1, 1));
+    }
+
+    #[test]
+    fn bilinear_filter_blends_neighbors() {
+        let mut p = TextureMap::new(quadrants(), UvMapping::Planar);
+        p.filter = TextureFilter::Bilinear;
+        // Exactly on the pixels, nothing is blended
+        assert!(p.uv_pattern_at(0.0, 1.0) == color!(1, 0, 0));
+        assert!(p.uv_pattern_at(1.0, 0.0) == color!(1, 1, 1));
+        // Halfway along the top row
+        assert!(p.uv_pattern_at(0.5, 1.0) == color!(0.5, 0.5, 0));
+        // The center is the average of all four
+        assert!(p.uv_pattern_at(0.5, 0.5) == color!(0.5, 0.5, 0.5));
+        // A quarter of the way between the centers of red and green
+        assert!(p.uv_pattern_at(0.375, 1.0) == color!(0.75, 0.25, 0));
+    }
+
+    #[test]
+    fn texture_wrapped_around_sphere() {
+        // A gradient from black on the left to white on the right
+        let mut image = Canvas::new(11, 1);
+        for x in 0..11 {
+            let value = x as f64 / 10.0;
+            image.write_pixel(x, 0, color!(value, value, value));
+        }
+        let p = TextureMap::new(Rc::new(image), UvMapping::Spherical);
+
+        let mut s = Sphere::new();
+        s.set_transform(scaling(2.0, 2.0, 2.0));
+        // u is 0.25 on +x and 0.75 on -x, which are closest to the
+        // centers of the third and the ninth pixels
+        assert!(p.pattern_at_shape(&s, point!(2, 0, 0)) == color!(0.2, 0.2, 0.2));
+        assert!(p.pattern_at_shape(&s, point!(-2, 0, 0)) == color!(0.8, 0.8, 0.8));
+    }
+
+    #[test]
+    fn texture_on_cube_faces() {
+        // One color per cell of the 4 by 3 cross
+        let mut image = Canvas::new(4, 3);
+        let front = color!(1, 0, 0);
+        let up = color!(0, 1, 0);
+        image.write_pixel(1, 1, front);
+        image.write_pixel(1, 0, up);
+        let p = TextureMap::new(Rc::new(image), UvMapping::Cube);
+        assert!(p.pattern_at(point!(0, 0, 1)) == front);
+        assert!(p.pattern_at(point!(0, 1, 0)) == up);
+        assert!(p.pattern_at(point!(1, 0, 0)) == color!(0, 0, 0));
+    }
+
+    #[test]
+    fn cube_face_edges_dont_blend_with_neighbors() {
+        // Two by two pixels per face, a red front between a green left
+        // face and a blue top
+        let mut image = Canvas::new(8, 6);
+        for (x, y) in [(2, 2), (3, 2), (2, 3), (3, 3)] {
+            image.write_pixel(x, y, color!(1, 0, 0));
+            image.write_pixel(x - 2, y, color!(0, 1, 0));
+            image.write_pixel(x, y - 2, color!(0, 0, 1));
+        }
+        let mut p = TextureMap::new(Rc::new(image), UvMapping::Cube);
+        p.filter = TextureFilter::Bilinear;
+        // Next to the left edge and the top edge of the front face
+        assert!(p.pattern_at(point!(-0.99, 0, 1)) == color!(1, 0, 0));
+        assert!(p.pattern_at(point!(0, 0.99, 1)) == color!(1, 0, 0));
+        assert!(p.pattern_at(point!(-0.99, 0.99, 1)) == color!(1, 0, 0));
+        // The same spots seen from the left face and the top
+        assert!(p.pattern_at(point!(-1, 0, 0.99)) == color!(0, 1, 0));
+        assert!(p.pattern_at(point!(0, 1, 0.99)) == color!(0, 0, 1));
+    }
+}
@@ -0,0 +1,265 @@
+//! Functions that wrap a flat `(u, v)` texture around a shape, taking
+//! an object space `Point` to coordinates between 0.0 and 1.0
+use crate::tuple::{Point, Tuple, Vector};
+use std::f64::consts::PI;
+
+/// The ways a `TextureMap` can be wrapped around a shape
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum UvMapping {
+    /// Around a unit sphere, see `spherical_map`
+    Spherical,
+    /// Tiled on the xz plane, see `planar_map`
+    Planar,
+    /// Around a unit cylinder, see `cylindrical_map`
+    Cylindrical,
+    /// On the faces of a unit cube, see `cube_map`
+    Cube,
+}
+
+impl UvMapping {
+    /// The `(u, v)` coordinates of `point`, with the six faces of a
+    /// `Cube` mapping unfolded into a single image
+    ///
+    /// The faces are laid out as a cross four faces wide and three
+    /// faces high, with the left, front, right and back faces in the
+    /// middle row.
+    pub fn map(&self, point: Point) -> (f64, f64) {
+        match self {
+            UvMapping::Spherical => spherical_map(point),
+            UvMapping::Planar => planar_map(point),
+            UvMapping::Cylindrical => cylindrical_map(point),
+            UvMapping::Cube => {
+                let (face, u, v) = cube_map(point);
+                let (column, row) = face.cross_position();
+                ((column as f64 + u) / 4.0, (row as f64 + v) / 3.0)
+            }
+        }
+    }
+}
+
+/// Map a `Point` on a unit sphere, `u` goes around the y axis and `v`
+/// from the south pole to the north pole
+///
+/// # Examples
+/// ```
+/// use ray_tracer::patterns::uv_mapping::spherical_map;
+/// use ray_tracer::tuple::{Point, Tuple};
+/// use ray_tracer::point;
+///
+/// assert_eq!(spherical_map(point!(0, 0, -1)), (0.0, 0.5));
+/// ```
+pub fn spherical_map(point: Point) -> (f64, f64) {
+    // The azimuthal angle, between -PI and PI
+    let theta = point.x.atan2(point.z);
+    let radius = Vector::new(point.x, point.y, point.z).magnitude();
+    // The polar angle, between 0 and PI
+    let phi = (point.y / radius).acos();
+
+    let raw_u = theta / (2.0 * PI);
+    // Flip u so that it increases counterclockwise seen from above
+    let u = 1.0 - (raw_u + 0.5);
+    let v = 1.0 - phi / PI;
+
+    (u, v)
+}
+
+/// Map a `Point` on the xz plane, repeating the texture every unit
+pub fn planar_map(point: Point) -> (f64, f64) {
+    (point.x.rem_euclid(1.0), point.z.rem_euclid(1.0))
+}
+
+/// Map a `Point` on a unit cylinder, `u` goes around the y axis like
+/// `spherical_map` and `v` repeats every unit along it
+pub fn cylindrical_map(point: Point) -> (f64, f64) {
+    let theta = point.x.atan2(point.z);
+    let raw_u = theta / (2.0 * PI);
+    let u = 1.0 - (raw_u + 0.5);
+
+    (u, point.y.rem_euclid(1.0))
+}
+
+/// A face of a unit cube
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CubeFace {
+    Left,
+    Right,
+    Front,
+    Back,
+    Up,
+    Down,
+}
+
+impl CubeFace {
+    /// The face of a unit cube closest to `point`, the one along the
+    /// largest coordinate
+    pub fn from_point(point: Point) -> CubeFace {
+        let abs_x = point.x.abs();
+        let coord = abs_x.max(point.y.abs()).max(point.z.abs());
+
+        if coord == point.x {
+            CubeFace::Right
+        } else if coord == -point.x {
+            CubeFace::Left
+        } else if coord == point.y {
+            CubeFace::Up
+        } else if coord == -point.y {
+            CubeFace::Down
+        } else if coord == point.z {
+            CubeFace::Front
+        } else {
+            CubeFace::Back
+        }
+    }
+
+    /// The column and row of the face in the unfolded cross, counted
+    /// from the bottom left
+    pub(crate) fn cross_position(&self) -> (usize, usize) {
+        match self {
+            CubeFace::Left => (0, 1),
+            CubeFace::Front => (1, 1),
+            CubeFace::Right => (2, 1),
+            CubeFace::Back => (3, 1),
+            CubeFace::Up => (1, 2),
+            CubeFace::Down => (1, 0),
+        }
+    }
+}
+
+/// Map a `Point` on a unit cube to the face it lies on, and the
+/// `(u, v)` coordinates on that face
+///
+/// Each face is seen from outside of the cube, with `v` going up for
+/// the side faces and towards the front for the top and bottom ones.
+pub fn cube_map(point: Point) -> (CubeFace, f64, f64) {
+    let wrap = |value: f64| value.rem_euclid(2.0) / 2.0;
+    let face = CubeFace::from_point(point);
+    let (x, y, z) = (point.x, point.y, point.z);
+
+    let (u, v) = match face {
+        CubeFace::Front => (wrap(x + 1.0), wrap(y + 1.0)),
+        CubeFace::Back => (wrap(1.0 - x), wrap(y + 1.0)),
+        CubeFace::Left => (wrap(z + 1.0), wrap(y + 1.0)),
+        CubeFace::Right => (wrap(1.0 - z), wrap(y + 1.0)),
+        CubeFace::Up => (wrap(x + 1.0), wrap(1.0 - z)),
+        CubeFace::Down => (wrap(x + 1.0), wrap(z + 1.0)),
+    };
+
+    (face, u, v)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::point;
+    use std::f64::consts::FRAC_1_SQRT_2;
+
+    fn assert_uv(actual: (f64, f64), expected: (f64, f64)) {
+        assert!((actual.0 - expected.0).abs() < 1e-9, "{:?}", actual);
+        assert!((actual.1 - expected.1).abs() < 1e-9, "{:?}", actual);
+    }
+
+    #[test]
+    fn spherical_mapping_on_3d_points() {
+        let cases = [
+            (point!(0, 0, -1), (0.0, 0.5)),
+            (point!(1, 0, 0), (0.25, 0.5)),
+            (point!(0, 0, 1), (0.5, 0.5)),
+            (point!(-1, 0, 0), (0.75, 0.5)),
+            (point!(0, 1, 0), (0.5, 1.0)),
+            (point!(0, -1, 0), (0.5, 0.0)),
+            (point!(FRAC_1_SQRT_2, FRAC_1_SQRT_2, 0), (0.25, 0.75)),
+        ];
+        for (point, uv) in cases {
+            assert_uv(spherical_map(point), uv);
+        }
+    }
+
+    #[test]
+    fn planar_mapping_on_3d_points() {
+        let cases = [
+            (point!(0.25, 0, 0.5), (0.25, 0.5)),
+            (point!(0.25, 0, -0.25), (0.25, 0.75)),
+            (point!(0.25, 0.5, -0.25), (0.25, 0.75)),
+            (point!(1.25, 0, 0.5), (0.25, 0.5)),
+            (point!(0.25, 0, -1.75), (0.25, 0.25)),
+            (point!(1, 0, -1), (0.0, 0.0)),
+            (point!(0, 0, 0), (0.0, 0.0)),
+        ];
+        for (point, uv) in cases {
+            assert_uv(planar_map(point), uv);
+        }
+    }
+
+    // The points are the book's, rounded to five places
+    #[allow(clippy::approx_constant)]
+    #[test]
+    fn cylindrical_mapping_on_3d_points() {
+        let cases = [
+            (point!(0, 0, -1), (0.0, 0.0)),
+            (point!(0, 0.5, -1), (0.0, 0.5)),
+            (point!(0, 1, -1), (0.0, 0.0)),
+            (point!(0.70711, 0.5, -0.70711), (0.125, 0.5)),
+            (point!(1, 0.5, 0), (0.25, 0.5)),
+            (point!(0.70711, 0.5, 0.70711), (0.375, 0.5)),
+            (point!(0, -0.25, 1), (0.5, 0.75)),
+            (point!(-0.70711, 0.5, 0.70711), (0.625, 0.5)),
+            (point!(-1, 1.25, 0), (0.75, 0.25)),
+            (point!(-0.70711, 0.5, -0.70711), (0.875, 0.5)),
+        ];
+        for (point, uv) in cases {
+            assert_uv(cylindrical_map(point), uv);
+        }
+    }
+
+    #[test]
+    fn identify_cube_faces() {
+        let cases = [
+            (point!(-1, 0.5, -0.25), CubeFace::Left),
+            (point!(1.1, -0.75, 0.8), CubeFace::Right),
+            (point!(0.1, 0.6, 0.9), CubeFace::Front),
+            (point!(-0.7, 0, -2), CubeFace::Back),
+            (point!(0.5, 1, 0.9), CubeFace::Up),
+            (point!(-0.2, -1.3, 1.1), CubeFace::Down),
+        ];
+        for (point, face) in cases {
+            assert_eq!(CubeFace::from_point(point), face);
+        }
+    }
+
+    #[test]
+    fn cube_mapping_on_each_face() {
+        let cases = [
+            (point!(-0.5, 0.5, 1), CubeFace::Front, (0.25, 0.75)),
+            (point!(0.5, -0.5, 1), CubeFace::Front, (0.75, 0.25)),
+            (point!(0.5, 0.5, -1), CubeFace::Back, (0.25, 0.75)),
+            (point!(-0.5, -0.5, -1), CubeFace::Back, (0.75, 0.25)),
+            (point!(-1, 0.5, -0.5), CubeFace::Left, (0.25, 0.75)),
+            (point!(-1, -0.5, 0.5), CubeFace::Left, (0.75, 0.25)),
+            (point!(1, 0.5, 0.5), CubeFace::Right, (0.25, 0.75)),
+            (point!(1, -0.5, -0.5), CubeFace::Right, (0.75, 0.25)),
+            (point!(-0.5, 1, -0.5), CubeFace::Up, (0.25, 0.75)),
+            (point!(0.5, 1, 0.5), CubeFace::Up, (0.75, 0.25)),
+            (point!(-0.5, -1, 0.5), CubeFace::Down, (0.25, 0.75)),
+            (point!(0.5, -1, -0.5), CubeFace::Down, (0.75, 0.25)),
+        ];
+        for (point, face, uv) in cases {
+            let (actual_face, u, v) = cube_map(point);
+            assert_eq!(actual_face, face);
+            assert_uv((u, v), uv);
+        }
+    }
+
+    #[test]
+    fn cube_mapping_unfolds_faces_into_a_cross() {
+        assert_uv(
+            UvMapping::Cube.map(point!(-0.5, 0.5, 1)),
+            (0.3125, 0.58333333333),
+        );
+        assert_uv(UvMapping::Cube.map(point!(0.5, 1, 0.5)), (0.4375, 0.75));
+        assert_uv(
+            UvMapping::Cube.map(point!(0.5, -1, -0.5)),
+            (0.4375, 0.08333333333),
+        );
+        assert_uv(UvMapping::Spherical.map(point!(0, 0, -1)), (0.0, 0.5));
+    }
+}
@@ -0,0 +1,78 @@
+use crate::tuple::{Matrix4, Point, Vector};
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Ray {
+    pub origin: Point,
+    pub direction: Vector,
+}
+
+impl Ray {
+    pub fn new(origin: Point, direction: Vector) -> Ray {
+        Ray { origin, direction }
+    }
+
+    /// Compute the `Point` at a distance `t` along the `Ray`
+    ///
+    /// # Examples
+    /// ```
+    /// use ray_tracer::ray::Ray;
+    /// use ray_tracer::tuple::{Point, Tuple, Vector};
+    /// use ray_tracer::{point, vector};
+    ///
+    /// let r = Ray::new(point!(2, 3, 4), vector!(1, 0, 0));
+    /// assert!(r.position(2.5) == point!(4.5, 3, 4));
+    /// ```
+    pub fn position(&self, t: f64) -> Point {
+        self.origin + self.direction * t
+    }
+
+    /// Apply the transformation `m` to the `Ray`, returning a new `Ray`
+    ///
+    /// The direction is not normalized afterwards, so that distances
+    /// along the transformed `Ray` match the original ones.
+    pub fn transform(&self, m: &Matrix4) -> Ray {
+        Ray::new(*m * self.origin, *m * self.direction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transform::{scaling, translation};
+    use crate::tuple::Tuple;
+    use crate::{point, vector};
+
+    #[test]
+    fn create_ray() {
+        let origin = point!(1, 2, 3);
+        let direction = vector!(4, 5, 6);
+        let r = Ray::new(origin, direction);
+        assert!(r.origin == origin);
+        assert!(r.direction == direction);
+    }
+
+    #[test]
+    fn point_from_distance() {
+        let r = Ray::new(point!(2, 3, 4), vector!(1, 0, 0));
+        assert!(r.position(0.0) == point!(2, 3, 4));
+        assert!(r.position(1.0) == point!(3, 3, 4));
+        assert!(r.position(-1.0) == point!(1, 3, 4));
+        assert!(r.position(2.5) == point!(4.5, 3, 4));
+    }
+
+    #[test]
+    fn translate_ray() {
+        let r = Ray::new(point!(1, 2, 3), vector!(0, 1, 0));
+        let r2 = r.transform(&translation(3.0, 4.0, 5.0));
+        assert!(r2.origin == point!(4, 6, 8));
+        assert!(r2.direction == vector!(0, 1, 0));
+    }
+
+    #[test]
+    fn scale_ray() {
+        let r = Ray::new(point!(1, 2, 3), vector!(0, 1, 0));
+        let r2 = r.transform(&scaling(2.0, 3.0, 4.0));
+        assert!(r2.origin == point!(2, 6, 12));
+        assert!(r2.direction == vector!(0, 3, 0));
+    }
+}
@@ -0,0 +1,568 @@
+use crate::bounds::Bounds;
+use crate::intersection::{Intersection, Intersections};
+use crate::ray::Ray;
+use crate::shapes::{update_children, Shape, ShapeData};
+use crate::tuple::{Point, Vector};
+use std::cell::Cell;
+
+/// The number of buckets the centroids are sorted into when looking
+/// for the cheapest split
+const BIN_COUNT: usize = 12;
+
+/// The cost of visiting a node, relative to intersecting a primitive
+const TRAVERSAL_COST: f64 = 1.0;
+
+/// A node of the flattened tree
+///
+/// Nodes are stored in depth-first order, so the first child of an
+/// interior node is always the next one in the array.
+#[derive(Clone, Debug)]
+struct BvhNode {
+    // Updated in place when the primitives move, see `Bvh::refit`
+    bounds: Cell<Bounds>,
+    // For a leaf, the first of its primitives; for an interior node,
+    // the index of its second child
+    offset: usize,
+    // Zero for interior nodes
+    count: usize,
+}
+
+impl BvhNode {
+    fn is_leaf(&self) -> bool {
+        self.count > 0
+    }
+}
+
+/// A primitive while the tree is being built
+struct BuildItem {
+    index: usize,
+    bounds: Bounds,
+    centroid: Point,
+}
+
+/// Numbers that describe the shape of a `Bvh`
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct BvhStats {
+    pub node_count: usize,
+    pub leaf_count: usize,
+    /// The number of nodes on the longest path from the root to a
+    /// leaf, zero for an empty `Bvh`
+    pub depth: usize,
+    pub average_leaf_size: f64,
+}
+
+/// A bounding volume hierarchy built with the surface area heuristic
+///
+/// Unlike a divided `Group`, the whole tree is stored in a single
+/// array of nodes and traversed with a stack, and every leaf refers to
+/// a contiguous range of primitives. Shapes with infinite `Bounds`,
+/// like a `Plane`, are kept out of the tree and always tested.
+///
+/// # Examples
+/// ```
+/// use ray_tracer::ray::Ray;
+/// use ray_tracer::shapes::{Bvh, Shape, Sphere};
+/// use ray_tracer::transform::translation;
+/// use ray_tracer::tuple::{Point, Tuple, Vector};
+/// use ray_tracer::{point, vector};
+///
+/// let spheres = (0..100)
+///     .map(|i| {
+///         let mut s = Sphere::new();
+///         s.set_transform(translation(i as f64 * 3.0, 0.0, 0.0));
+///         Box::new(s) as Box<dyn Shape>
+///     })
+///     .collect();
+/// let bvh = Bvh::new(spheres, 4);
+/// assert!(bvh.stats().depth > 1);
+///
+/// let r = Ray::new(point!(30, 0, -5), vector!(0, 0, 1));
+/// assert_eq!(bvh.intersect(&r).len(), 2);
+/// ```
+#[derive(Debug)]
+pub struct Bvh {
+    data: ShapeData,
+    nodes: Vec<BvhNode>,
+    // Ordered so that every leaf covers a contiguous range
+    primitives: Vec<Box<dyn Shape>>,
+    unbounded: Vec<Box<dyn Shape>>,
+    max_leaf_size: usize,
+    // Set when the primitives may have been moved through
+    // `children_mut`
+    refit_needed: Cell<bool>,
+}
+
+impl Bvh {
+    /// Build a `Bvh` over `children`, with at most `max_leaf_size`
+    /// primitives in each leaf
+    ///
+    /// Leaves can only be larger when their primitives all have the
+    /// same center, since nothing can tell them apart.
+    ///
+    /// # Panics
+    /// If `max_leaf_size` is zero.
+    pub fn new(children: Vec<Box<dyn Shape>>, max_leaf_size: usize) -> Bvh {
+        assert!(max_leaf_size > 0, "the leaves of a BVH can't be empty");
+
+        let (bounded, unbounded): (Vec<_>, Vec<_>) = children
+            .into_iter()
+            .partition(|child| child.bounds().transform(child.transform()).is_finite());
+
+        let mut items: Vec<BuildItem> = bounded
+            .iter()
+            .enumerate()
+            .map(|(index, child)| {
+                let bounds = child.bounds().transform(child.transform());
+                BuildItem {
+                    index,
+                    bounds,
+                    centroid: bounds.center(),
+                }
+            })
+            .collect();
+
+        let mut nodes = Vec::new();
+        if !items.is_empty() {
+            build_subtree(&mut items, 0, &mut nodes, max_leaf_size);
+        }
+
+        // Put the primitives in the order of the leaves
+        let mut slots: Vec<Option<Box<dyn Shape>>> = bounded.into_iter().map(Some).collect();
+        let primitives = items
+            .iter()
+            .map(|item| slots[item.index].take().unwrap())
+            .collect();
+
+        let mut bvh = Bvh {
+            data: ShapeData::new(),
+            nodes,
+            primitives,
+            unbounded,
+            max_leaf_size,
+            refit_needed: Cell::new(false),
+        };
+        update_children(&mut bvh);
+        // Only the transformations above the children changed, which
+        // doesn't move them relative to the `Bvh`
+        bvh.refit_needed.set(false);
+
+        bvh
+    }
+
+    pub fn max_leaf_size(&self) -> usize {
+        self.max_leaf_size
+    }
+
+    /// Every primitive of the `Bvh`, including the ones that are kept
+    /// out of the tree
+    pub fn children(&self) -> impl Iterator<Item = &dyn Shape> {
+        self.primitives
+            .iter()
+            .chain(self.unbounded.iter())
+            .map(|child| child.as_ref())
+    }
+
+    /// Compute the `Bounds` of every node again if the primitives may
+    /// have moved, the shape of the tree stays the same
+    fn refit(&self) {
+        if !self.refit_needed.replace(false) {
+            return;
+        }
+
+        // Children always come after their parent, so going backwards
+        // updates them first
+        for (index, node) in self.nodes.iter().enumerate().rev() {
+            let bounds = if node.is_leaf() {
+                self.primitives[node.offset..node.offset + node.count]
+                    .iter()
+                    .fold(Bounds::from_points(&[]), |acc, child| {
+                        acc.merge(&child.bounds().transform(child.transform()))
+                    })
+            } else {
+                let first = self.nodes[index + 1].bounds.get();
+                first.merge(&self.nodes[node.offset].bounds.get())
+            };
+            node.bounds.set(bounds);
+        }
+    }
+
+    /// Walk the tree to describe its shape
+    pub fn stats(&self) -> BvhStats {
+        let mut stats = BvhStats {
+            node_count: self.nodes.len(),
+            leaf_count: 0,
+            depth: 0,
+            average_leaf_size: 0.0,
+        };
+        if self.nodes.is_empty() {
+            return stats;
+        }
+
+        let mut stack = vec![(0, 1)];
+        while let Some((index, depth)) = stack.pop() {
+            let node = &self.nodes[index];
+            stats.depth = stats.depth.max(depth);
+            if node.is_leaf() {
+                stats.leaf_count += 1;
+            } else {
+                stack.push((index + 1, depth + 1));
+                stack.push((node.offset, depth + 1));
+            }
+        }
+        stats.average_leaf_size = self.primitives.len() as f64 / stats.leaf_count as f64;
+
+        stats
+    }
+}
+
+/// Add the node for `items` and all of its descendants to `nodes`,
+/// returning its index
+///
+/// `items` is reordered so that every leaf covers a contiguous range,
+/// `first` is the position of `items[0]` among all the primitives.
+fn build_subtree(
+    items: &mut [BuildItem],
+    first: usize,
+    nodes: &mut Vec<BvhNode>,
+    max_leaf_size: usize,
+) -> usize {
+    let bounds = items.iter().fold(Bounds::from_points(&[]), |acc, item| {
+        acc.merge(&item.bounds)
+    });
+    let index = nodes.len();
+
+    match split_items(items, &bounds, max_leaf_size) {
+        Some(mid) => {
+            nodes.push(BvhNode {
+                bounds: Cell::new(bounds),
+                offset: 0,
+                count: 0,
+            });
+            let (left, right) = items.split_at_mut(mid);
+            build_subtree(left, first, nodes, max_leaf_size);
+            let second = build_subtree(right, first + mid, nodes, max_leaf_size);
+            nodes[index].offset = second;
+        }
+        None => nodes.push(BvhNode {
+            bounds: Cell::new(bounds),
+            offset: first,
+            count: items.len(),
+        }),
+    }
+
+    index
+}
+
+/// Find the cheapest way to split `items` according to the surface
+/// area heuristic, and move them to each side of the returned index
+///
+/// `None` means that the items should stay together in a leaf.
+fn split_items(items: &mut [BuildItem], bounds: &Bounds, max_leaf_size: usize) -> Option<usize> {
+    if items.len() <= 1 {
+        return None;
+    }
+
+    let centroids =
+        Bounds::from_points(&items.iter().map(|item| item.centroid).collect::<Vec<_>>());
+    let axis_of = |p: Point, axis: usize| [p.x, p.y, p.z][axis];
+    let bin_of = |item: &BuildItem, axis: usize| {
+        let min = axis_of(centroids.min, axis);
+        let extent = axis_of(centroids.max, axis) - min;
+        let bin = ((axis_of(item.centroid, axis) - min) / extent * BIN_COUNT as f64) as usize;
+        bin.min(BIN_COUNT - 1)
+    };
+
+    // (cost, axis, last bin on the left side)
+    let mut best: Option<(f64, usize, usize)> = None;
+    for axis in 0..3 {
+        if axis_of(centroids.max, axis) - axis_of(centroids.min, axis) <= 0.0 {
+            continue;
+        }
+
+        let mut bins = [(0, Bounds::from_points(&[])); BIN_COUNT];
+        for item in items.iter() {
+            let bin = &mut bins[bin_of(item, axis)];
+            bin.0 += 1;
+            bin.1 = bin.1.merge(&item.bounds);
+        }
+
+        // Every plane between two bins is a candidate
+        for split in 0..BIN_COUNT - 1 {
+            let (left, right) = bins.split_at(split + 1);
+            let side = |bins: &[(usize, Bounds)]| {
+                bins.iter()
+                    .fold((0, Bounds::from_points(&[])), |(count, b), (n, nb)| {
+                        (count + n, b.merge(nb))
+                    })
+            };
+            let (left_count, left_bounds) = side(left);
+            let (right_count, right_bounds) = side(right);
+            if left_count == 0 || right_count == 0 {
+                continue;
+            }
+
+            let cost = TRAVERSAL_COST
+                + (left_count as f64 * left_bounds.surface_area()
+                    + right_count as f64 * right_bounds.surface_area())
+                    / bounds.surface_area();
+            if best.is_none_or(|(best_cost, _, _)| cost < best_cost) {
+                best = Some((cost, axis, split));
+            }
+        }
+    }
+
+    let (cost, axis, split) = best?;
+    let leaf_cost = items.len() as f64;
+    if items.len() <= max_leaf_size && cost >= leaf_cost {
+        return None;
+    }
+
+    let mut mid = 0;
+    for i in 0..items.len() {
+        if bin_of(&items[i], axis) <= split {
+            items.swap(i, mid);
+            mid += 1;
+        }
+    }
+
+    Some(mid)
+}
+
+impl Shape for Bvh {
+    fn data(&self) -> &ShapeData {
+        &self.data
+    }
+
+    fn data_mut(&mut self) -> &mut ShapeData {
+        &mut self.data
+    }
+
+    /// The primitives can be moved through the returned references,
+    /// so the `Bounds` of the nodes are refitted before the next use
+    fn children_mut(&mut self) -> Vec<&mut Box<dyn Shape>> {
+        self.refit_needed.set(true);
+        self.primitives
+            .iter_mut()
+            .chain(self.unbounded.iter_mut())
+            .collect()
+    }
+
+    fn includes(&self, other: &dyn Shape) -> bool {
+        std::ptr::addr_eq(self, other) || self.children().any(|child| child.includes(other))
+    }
+
+    /// Walk the tree with an explicit stack, only the leaves whose
+    /// `Bounds` are hit by the `ray` have their primitives tested
+    fn local_intersect(&self, ray: &Ray) -> Intersections<'_> {
+        self.refit();
+        let mut xs = Vec::new();
+        for child in &self.unbounded {
+            xs.extend(child.intersect(ray));
+        }
+
+        let mut stack = Vec::with_capacity(64);
+        if !self.nodes.is_empty() {
+            stack.push(0);
+        }
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+            if !node.bounds.get().intersects(ray) {
+                continue;
+            }
+
+            if node.is_leaf() {
+                for child in &self.primitives[node.offset..node.offset + node.count] {
+                    xs.extend(child.intersect(ray));
+                }
+            } else {
+                stack.push(node.offset);
+                stack.push(index + 1);
+            }
+        }
+
+        Intersections::new(xs)
+    }
+
+    fn local_normal_at(&self, _point: Point, _hit: &Intersection) -> Vector {
+        unreachable!("the normal of a BVH is computed by its children")
+    }
+
+    /// The `Bounds` of every child, transformed into the space of the
+    /// `Bvh`
+    fn bounds(&self) -> Bounds {
+        self.refit();
+        self.unbounded.iter().fold(
+            self.nodes
+                .first()
+                .map_or(Bounds::from_points(&[]), |root| root.bounds.get()),
+            |acc, child| acc.merge(&child.bounds().transform(child.transform())),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shapes::{Group, Plane, Sphere};
+    use crate::transform::{scaling, translation};
+    use crate::tuple::Tuple;
+    use crate::{point, vector};
+
+    /// A grid of `n` by `n` small spheres in the z = 0 plane
+    fn sphere_grid(n: usize) -> Vec<Box<dyn Shape>> {
+        let mut spheres: Vec<Box<dyn Shape>> = Vec::new();
+        for x in 0..n {
+            for y in 0..n {
+                let mut s = Sphere::new();
+                s.set_transform(
+                    translation(x as f64 * 2.0, y as f64 * 2.0, 0.0) * scaling(0.5, 0.5, 0.5),
+                );
+                spheres.push(Box::new(s));
+            }
+        }
+        spheres
+    }
+
+    #[test]
+    fn empty_bvh() {
+        let bvh = Bvh::new(Vec::new(), 4);
+        let r = Ray::new(point!(0, 0, -5), vector!(0, 0, 1));
+        assert!(bvh.intersect(&r).is_empty());
+        assert_eq!(
+            bvh.stats(),
+            BvhStats {
+                node_count: 0,
+                leaf_count: 0,
+                depth: 0,
+                average_leaf_size: 0.0
+            }
+        );
+    }
+
+    #[test]
+    fn small_bvh_is_a_single_leaf() {
+        let bvh = Bvh::new(vec![Box::new(Sphere::new())], 4);
+        let stats = bvh.stats();
+        assert_eq!(stats.node_count, 1);
+        assert_eq!(stats.depth, 1);
+        assert_eq!(stats.average_leaf_size, 1.0);
+        assert!(bvh.bounds() == Sphere::new().bounds());
+    }
+
+    #[test]
+    fn nodes_are_stored_depth_first() {
+        let bvh = Bvh::new(sphere_grid(8), 2);
+        let mut covered = 0;
+        for (index, node) in bvh.nodes.iter().enumerate() {
+            if node.is_leaf() {
+                assert!(node.count <= 2);
+                assert_eq!(node.offset, covered);
+                covered += node.count;
+            } else {
+                // Both children are inside the node
+                assert!(node.offset > index + 1);
+                let bounds = node.bounds.get();
+                assert!(bounds.contains_bounds(&bvh.nodes[index + 1].bounds.get()));
+                assert!(bounds.contains_bounds(&bvh.nodes[node.offset].bounds.get()));
+            }
+        }
+        assert_eq!(covered, 64);
+    }
+
+    #[test]
+    fn leaf_size_is_configurable() {
+        for max_leaf_size in [1, 4, 16] {
+            let bvh = Bvh::new(sphere_grid(10), max_leaf_size);
+            assert!(bvh.nodes.iter().all(|node| node.count <= max_leaf_size));
+            let stats = bvh.stats();
+            assert_eq!(stats.node_count, stats.leaf_count * 2 - 1);
+        }
+        let stats = Bvh::new(sphere_grid(10), 1).stats();
+        assert_eq!(stats.leaf_count, 100);
+        assert_eq!(stats.average_leaf_size, 1.0);
+        assert!(stats.depth >= 8);
+    }
+
+    #[test]
+    fn overlapping_primitives_are_not_split_when_it_does_not_pay() {
+        let spheres = || {
+            (0..4)
+                .map(|i| {
+                    let mut s = Sphere::new();
+                    s.set_transform(translation(i as f64 * 0.01, 0.0, 0.0));
+                    Box::new(s) as Box<dyn Shape>
+                })
+                .collect()
+        };
+        assert_eq!(Bvh::new(spheres(), 4).stats().leaf_count, 1);
+        // A smaller leaf size forces a split anyway
+        assert_eq!(Bvh::new(spheres(), 2).stats().leaf_count, 2);
+    }
+
+    #[test]
+    fn identical_primitives_share_a_leaf() {
+        let spheres = (0..5)
+            .map(|_| Box::new(Sphere::new()) as Box<dyn Shape>)
+            .collect();
+        let stats = Bvh::new(spheres, 2).stats();
+        assert_eq!(stats.leaf_count, 1);
+        assert_eq!(stats.average_leaf_size, 5.0);
+    }
+
+    #[test]
+    fn intersect_like_a_group() {
+        let bvh = Bvh::new(sphere_grid(10), 3);
+        let mut group = Group::new();
+        for child in sphere_grid(10) {
+            group.add_child(child);
+        }
+
+        for (x, y) in [(0.0, 0.0), (4.2, 6.0), (9.0, 9.0), (19.0, 3.0), (7.0, 7.0)] {
+            let r = Ray::new(point!(x, y, -5), vector!(0.1, 0.05, 1).normalize());
+            let expected: Vec<f64> = group.intersect(&r).iter().map(|i| i.t).collect();
+            let actual: Vec<f64> = bvh.intersect(&r).iter().map(|i| i.t).collect();
+            assert_eq!(actual, expected);
+        }
+    }
+
+    #[test]
+    fn unbounded_shapes_are_always_tested() {
+        let mut children = sphere_grid(3);
+        let mut floor = Plane::new();
+        floor.set_transform(translation(0.0, -10.0, 0.0));
+        children.push(Box::new(floor));
+        let bvh = Bvh::new(children, 1);
+        assert_eq!(bvh.stats().leaf_count, 9);
+        assert_eq!(bvh.children().count(), 10);
+
+        let r = Ray::new(point!(100, 0, 0), vector!(0, -1, 0));
+        let xs = bvh.intersect(&r);
+        assert_eq!(xs.len(), 1);
+        assert_eq!(xs[0].t, 10.0);
+    }
+
+    #[test]
+    fn transformed_bvh() {
+        let mut bvh = Bvh::new(sphere_grid(4), 2);
+        bvh.set_transform(translation(0.0, 0.0, 5.0));
+        let r = Ray::new(point!(2, 2, 0), vector!(0, 0, 1));
+        let xs = bvh.intersect(&r);
+        assert_eq!(xs.len(), 2);
+        assert_eq!(xs[0].t, 4.5);
+        assert!(bvh.includes(xs[0].object));
+        let comps = xs[0].prepare_computations(&r, &xs);
+        assert!(comps.normalv == vector!(0, 0, -1));
+    }
+
+    #[test]
+    fn moved_primitive_is_still_hit() {
+        let mut bvh = Bvh::new(sphere_grid(4), 2);
+        bvh.children_mut()[0].set_transform(translation(20.0, 0.0, 0.0));
+        assert!(bvh.bounds().max.x == 21.0);
+
+        let r = Ray::new(point!(20, 0, -5), vector!(0, 0, 1));
+        let xs = bvh.intersect(&r);
+        assert_eq!(xs.len(), 2);
+        assert_eq!(xs[0].t, 4.0);
+    }
+}
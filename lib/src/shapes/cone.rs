@@ -0,0 +1,210 @@
+use crate::bounds::Bounds;
+use crate::intersection::{Intersection, Intersections};
+use crate::ray::Ray;
+use crate::shapes::cylinder::check_cap;
+use crate::shapes::{Shape, ShapeData};
+use crate::tuple::utils::EPSILON;
+use crate::tuple::{Point, Tuple, Vector};
+
+/// A double-napped cone around the y axis in its object space, with
+/// its apex at the origin and a radius equal to `|y|`
+///
+/// Like a `Cylinder`, it is infinite unless it is truncated with
+/// `minimum` and `maximum`, and only capped if `closed` is set.
+#[derive(Clone, Debug)]
+pub struct Cone {
+    data: ShapeData,
+    pub minimum: f64,
+    pub maximum: f64,
+    pub closed: bool,
+}
+
+impl Cone {
+    /// Create an infinite, open `Cone`
+    pub fn new() -> Cone {
+        Cone {
+            data: ShapeData::new(),
+            minimum: f64::NEG_INFINITY,
+            maximum: f64::INFINITY,
+            closed: false,
+        }
+    }
+
+    fn intersect_caps<'a>(&'a self, ray: &Ray, xs: &mut Vec<Intersection<'a>>) {
+        // Caps only matter if the cone is closed and could be hit
+        if !self.closed || ray.direction.y.abs() < EPSILON {
+            return;
+        }
+
+        // The radius of each cap is the `y` where it is placed
+        for y in [self.minimum, self.maximum] {
+            let t = (y - ray.origin.y) / ray.direction.y;
+            if check_cap(ray, t, y.abs()) {
+                xs.push(Intersection::new(t, self));
+            }
+        }
+    }
+}
+
+impl Default for Cone {
+    fn default() -> Self {
+        Cone::new()
+    }
+}
+
+impl Shape for Cone {
+    fn data(&self) -> &ShapeData {
+        &self.data
+    }
+
+    fn data_mut(&mut self) -> &mut ShapeData {
+        &mut self.data
+    }
+
+    fn local_intersect(&self, ray: &Ray) -> Intersections<'_> {
+        let (o, d) = (ray.origin, ray.direction);
+        let a = d.x.powi(2) - d.y.powi(2) + d.z.powi(2);
+        let b = 2.0 * o.x * d.x - 2.0 * o.y * d.y + 2.0 * o.z * d.z;
+        let c = o.x.powi(2) - o.y.powi(2) + o.z.powi(2);
+
+        let mut ts = Vec::new();
+        if a.abs() < EPSILON {
+            // The ray is parallel to one of the halves, so it can
+            // only hit the other one
+            if b.abs() >= EPSILON {
+                ts.push(-c / (2.0 * b));
+            }
+        } else {
+            let discriminant = b * b - 4.0 * a * c;
+            // Allow for rounding errors when the ray grazes the cone
+            if discriminant > -EPSILON {
+                let sqrt_disc = discriminant.max(0.0).sqrt();
+                ts.push((-b - sqrt_disc) / (2.0 * a));
+                ts.push((-b + sqrt_disc) / (2.0 * a));
+            }
+        }
+
+        let mut xs = Vec::new();
+        for t in ts {
+            let y = o.y + t * d.y;
+            if self.minimum < y && y < self.maximum {
+                xs.push(Intersection::new(t, self));
+            }
+        }
+
+        self.intersect_caps(ray, &mut xs);
+        Intersections::new(xs)
+    }
+
+    fn local_normal_at(&self, point: Point, _hit: &Intersection) -> Vector {
+        let distance = point.x.powi(2) + point.z.powi(2);
+
+        if distance < point.y.powi(2) && point.y >= self.maximum - EPSILON {
+            Vector::new(0.0, 1.0, 0.0)
+        } else if distance < point.y.powi(2) && point.y <= self.minimum + EPSILON {
+            Vector::new(0.0, -1.0, 0.0)
+        } else {
+            let y = if point.y > 0.0 {
+                -distance.sqrt()
+            } else {
+                distance.sqrt()
+            };
+            Vector::new(point.x, y, point.z)
+        }
+    }
+
+    /// The radius of the `Cone` is largest at whichever end is
+    /// furthest from the apex
+    fn bounds(&self) -> Bounds {
+        let limit = self.minimum.abs().max(self.maximum.abs());
+        Bounds::new(
+            Point::new(-limit, self.minimum, -limit),
+            Point::new(limit, self.maximum, limit),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{point, vector};
+
+    #[test]
+    fn ray_strikes_cone() {
+        let shape = Cone::new();
+        let cases = [
+            (point!(0, 0, -5), vector!(0, 0, 1), 5.0, 5.0),
+            (point!(0, 0, -5), vector!(1, 1, 1), 8.66025, 8.66025),
+            (point!(1, 1, -5), vector!(-0.5, -1, 1), 4.55006, 49.44994),
+        ];
+        for (origin, direction, t0, t1) in cases {
+            let r = Ray::new(origin, direction.normalize());
+            let xs = shape.local_intersect(&r);
+            assert_eq!(xs.len(), 2);
+            assert!((xs[0].t - t0).abs() < 1e-4);
+            assert!((xs[1].t - t1).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn ray_parallel_to_one_half() {
+        let shape = Cone::new();
+        let r = Ray::new(point!(0, 0, -1), vector!(0, 1, 1).normalize());
+        let xs = shape.local_intersect(&r);
+        assert_eq!(xs.len(), 1);
+        assert!((xs[0].t - 0.35355).abs() < 1e-5);
+    }
+
+    #[test]
+    fn intersect_caps_of_closed_cone() {
+        let mut shape = Cone::new();
+        shape.minimum = -0.5;
+        shape.maximum = 0.5;
+        shape.closed = true;
+        let cases = [
+            (point!(0, 0, -5), vector!(0, 1, 0), 0),
+            (point!(0, 0, -0.25), vector!(0, 1, 1), 2),
+            (point!(0, 0, -0.25), vector!(0, 1, 0), 4),
+        ];
+        for (origin, direction, count) in cases {
+            let r = Ray::new(origin, direction.normalize());
+            assert_eq!(shape.local_intersect(&r).len(), count);
+        }
+    }
+
+    #[test]
+    fn normal_on_cone() {
+        let shape = Cone::new();
+        let cases = [
+            (point!(0, 0, 0), vector!(0, 0, 0)),
+            (point!(1, 1, 1), vector!(1, -(2.0_f64.sqrt()), 1)),
+            (point!(-1, -1, 0), vector!(-1, 1, 0)),
+        ];
+        let hit = Intersection::new(0.0, &shape);
+        for (point, normal) in cases {
+            assert!(shape.local_normal_at(point, &hit) == normal);
+        }
+    }
+
+    #[test]
+    fn normal_on_cone_caps() {
+        let mut shape = Cone::new();
+        shape.minimum = -1.0;
+        shape.maximum = 2.0;
+        shape.closed = true;
+        let hit = Intersection::new(0.0, &shape);
+        assert!(shape.local_normal_at(point!(0.5, 2, 0), &hit) == vector!(0, 1, 0));
+        assert!(shape.local_normal_at(point!(0, -1, 0.5), &hit) == vector!(0, -1, 0));
+    }
+
+    #[test]
+    fn truncated_cone_bounds() {
+        let mut shape = Cone::new();
+        shape.minimum = -5.0;
+        shape.maximum = 3.0;
+        let b = shape.bounds();
+        assert!(b.min == point!(-5, -5, -5));
+        assert!(b.max == point!(5, 3, 5));
+        assert!(!Cone::new().bounds().is_finite());
+    }
+}
@@ -0,0 +1,321 @@
+use crate::bounds::Bounds;
+use crate::intersection::{Intersection, Intersections};
+use crate::ray::Ray;
+use crate::shapes::{update_children, Shape, ShapeData};
+use crate::tuple::{Point, Vector};
+
+/// The ways two shapes can be combined in a `Csg`
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CsgOperation {
+    /// Everything inside either of the shapes
+    Union,
+    /// Only what is inside both shapes
+    Intersection,
+    /// What is inside the left shape but not inside the right one
+    Difference,
+}
+
+impl CsgOperation {
+    /// Decide if an intersection is part of the combined surface
+    ///
+    /// `lhit` tells if the left shape was hit, while `inl` and `inr`
+    /// tell if the hit happened inside the left and right shapes.
+    pub fn intersection_allowed(&self, lhit: bool, inl: bool, inr: bool) -> bool {
+        match self {
+            CsgOperation::Union => (lhit && !inr) || (!lhit && !inl),
+            CsgOperation::Intersection => (lhit && inr) || (!lhit && inl),
+            CsgOperation::Difference => (lhit && !inr) || (!lhit && inl),
+        }
+    }
+}
+
+/// Constructive solid geometry, two shapes combined with an operation
+#[derive(Debug)]
+pub struct Csg {
+    data: ShapeData,
+    operation: CsgOperation,
+    left: Box<dyn Shape>,
+    right: Box<dyn Shape>,
+}
+
+impl Csg {
+    pub fn new(operation: CsgOperation, left: Box<dyn Shape>, right: Box<dyn Shape>) -> Csg {
+        let mut csg = Csg {
+            data: ShapeData::new(),
+            operation,
+            left,
+            right,
+        };
+        update_children(&mut csg);
+
+        csg
+    }
+
+    pub fn operation(&self) -> CsgOperation {
+        self.operation
+    }
+
+    pub fn left(&self) -> &dyn Shape {
+        self.left.as_ref()
+    }
+
+    pub fn right(&self) -> &dyn Shape {
+        self.right.as_ref()
+    }
+
+    /// Keep only the intersections that are part of the combined
+    /// surface, `xs` must be sorted by `t`
+    pub fn filter_intersections<'a>(&self, xs: &[Intersection<'a>]) -> Vec<Intersection<'a>> {
+        // Rays start outside of both shapes
+        let mut inl = false;
+        let mut inr = false;
+        let mut result = Vec::new();
+
+        for i in xs {
+            let lhit = self.left.includes(i.object);
+            if self.operation.intersection_allowed(lhit, inl, inr) {
+                result.push(*i);
+            }
+
+            // Every hit enters or leaves one of the shapes
+            if lhit {
+                inl = !inl;
+            } else {
+                inr = !inr;
+            }
+        }
+
+        result
+    }
+}
+
+impl Shape for Csg {
+    fn data(&self) -> &ShapeData {
+        &self.data
+    }
+
+    fn data_mut(&mut self) -> &mut ShapeData {
+        &mut self.data
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut Box<dyn Shape>> {
+        vec![&mut self.left, &mut self.right]
+    }
+
+    fn includes(&self, other: &dyn Shape) -> bool {
+        std::ptr::addr_eq(self, other) || self.left.includes(other) || self.right.includes(other)
+    }
+
+    fn divide(&mut self, threshold: usize) {
+        self.left.divide(threshold);
+        self.right.divide(threshold);
+    }
+
+    fn local_intersect(&self, ray: &Ray) -> Intersections<'_> {
+        if !self.bounds().intersects(ray) {
+            return Intersections::default();
+        }
+
+        let mut xs = Vec::new();
+        xs.extend(self.left.intersect(ray));
+        xs.extend(self.right.intersect(ray));
+        let xs = Intersections::new(xs);
+
+        Intersections::new(self.filter_intersections(&xs))
+    }
+
+    fn local_normal_at(&self, _point: Point, _hit: &Intersection) -> Vector {
+        unreachable!("the normal of a CSG is computed by its children")
+    }
+
+    /// The `Bounds` of both children, transformed into the space of
+    /// the `Csg`
+    fn bounds(&self) -> Bounds {
+        let left = self.left.bounds().transform(self.left.transform());
+        let right = self.right.bounds().transform(self.right.transform());
+        left.merge(&right)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shapes::{Cube, Group, Sphere};
+    use crate::transform::translation;
+    use crate::tuple::Tuple;
+    use crate::{point, vector};
+
+    #[test]
+    fn create_csg() {
+        let s1 = Sphere::new();
+        let s2 = Cube::new();
+        let c = Csg::new(CsgOperation::Union, Box::new(s1), Box::new(s2));
+        assert_eq!(c.operation(), CsgOperation::Union);
+        assert!(c.left().bounds() == Sphere::new().bounds());
+        assert!(c.right().bounds() == Cube::new().bounds());
+    }
+
+    #[test]
+    fn transform_csg_after_creating_it() {
+        let mut c = Csg::new(
+            CsgOperation::Union,
+            Box::new(Sphere::new()),
+            Box::new(Cube::new()),
+        );
+        c.set_transform(translation(5.0, 0.0, 0.0));
+        assert!(c.left().world_to_object(point!(5, 1, 0)) == point!(0, 1, 0));
+        assert!(c.right().world_to_object(point!(5, 1, 0)) == point!(0, 1, 0));
+    }
+
+    #[test]
+    fn evaluate_rules_for_operations() {
+        use CsgOperation::*;
+        // (operation, lhit, inl, inr, allowed)
+        let cases = [
+            (Union, true, true, true, false),
+            (Union, true, true, false, true),
+            (Union, true, false, true, false),
+            (Union, true, false, false, true),
+            (Union, false, true, true, false),
+            (Union, false, true, false, false),
+            (Union, false, false, true, true),
+            (Union, false, false, false, true),
+            (Intersection, true, true, true, true),
+            (Intersection, true, true, false, false),
+            (Intersection, true, false, true, true),
+            (Intersection, true, false, false, false),
+            (Intersection, false, true, true, true),
+            (Intersection, false, true, false, true),
+            (Intersection, false, false, true, false),
+            (Intersection, false, false, false, false),
+            (Difference, true, true, true, false),
+            (Difference, true, true, false, true),
+            (Difference, true, false, true, false),
+            (Difference, true, false, false, true),
+            (Difference, false, true, true, true),
+            (Difference, false, true, false, true),
+            (Difference, false, false, true, false),
+            (Difference, false, false, false, false),
+        ];
+        for (op, lhit, inl, inr, allowed) in cases {
+            assert_eq!(op.intersection_allowed(lhit, inl, inr), allowed);
+        }
+    }
+
+    #[test]
+    fn filter_list_of_intersections() {
+        let cases = [
+            (CsgOperation::Union, 0, 3),
+            (CsgOperation::Intersection, 1, 2),
+            (CsgOperation::Difference, 0, 1),
+        ];
+        for (operation, x0, x1) in cases {
+            let c = Csg::new(operation, Box::new(Sphere::new()), Box::new(Cube::new()));
+            let (s1, s2) = (c.left(), c.right());
+            let xs = [
+                Intersection::new(1.0, s1),
+                Intersection::new(2.0, s2),
+                Intersection::new(3.0, s1),
+                Intersection::new(4.0, s2),
+            ];
+            let result = c.filter_intersections(&xs);
+            assert_eq!(result.len(), 2);
+            assert!(result[0] == xs[x0]);
+            assert!(result[1] == xs[x1]);
+        }
+    }
+
+    #[test]
+    fn ray_misses_csg() {
+        let c = Csg::new(
+            CsgOperation::Union,
+            Box::new(Sphere::new()),
+            Box::new(Cube::new()),
+        );
+        let r = Ray::new(point!(0, 2, -5), vector!(0, 0, 1));
+        assert!(c.local_intersect(&r).is_empty());
+    }
+
+    #[test]
+    fn ray_hits_csg() {
+        let s1 = Sphere::new();
+        let mut s2 = Sphere::new();
+        s2.set_transform(translation(0.0, 0.0, 0.5));
+        let c = Csg::new(CsgOperation::Union, Box::new(s1), Box::new(s2));
+        let r = Ray::new(point!(0, 0, -5), vector!(0, 0, 1));
+        let xs = c.local_intersect(&r);
+        assert_eq!(xs.len(), 2);
+        assert_eq!(xs[0].t, 4.0);
+        assert!(std::ptr::addr_eq(xs[0].object, c.left()));
+        assert_eq!(xs[1].t, 6.5);
+        assert!(std::ptr::addr_eq(xs[1].object, c.right()));
+    }
+
+    #[test]
+    fn difference_drills_a_hole() {
+        // A cube with a smaller cube taken out of its middle, along z
+        let mut drill = Cube::new();
+        drill.set_transform(crate::transform::scaling(0.5, 0.5, 2.0));
+        let c = Csg::new(
+            CsgOperation::Difference,
+            Box::new(Cube::new()),
+            Box::new(drill),
+        );
+        let through_hole = Ray::new(point!(0, 0, -5), vector!(0, 0, 1));
+        assert!(c.intersect(&through_hole).is_empty());
+        let outside_hole = Ray::new(point!(0.75, 0, -5), vector!(0, 0, 1));
+        assert_eq!(c.intersect(&outside_hole).len(), 2);
+    }
+
+    #[test]
+    fn divide_csg_children() {
+        let mut s1 = Sphere::new();
+        s1.set_transform(translation(-1.5, 0.0, 0.0));
+        let mut s2 = Sphere::new();
+        s2.set_transform(translation(1.5, 0.0, 0.0));
+        let mut left = Group::new();
+        left.add_child(Box::new(s1));
+        left.add_child(Box::new(s2));
+
+        let mut s3 = Sphere::new();
+        s3.set_transform(translation(0.0, 0.0, -1.5));
+        let mut s4 = Sphere::new();
+        s4.set_transform(translation(0.0, 0.0, 1.5));
+        let mut right = Group::new();
+        right.add_child(Box::new(s3));
+        right.add_child(Box::new(s4));
+
+        let mut c = Csg::new(CsgOperation::Difference, Box::new(left), Box::new(right));
+        let r = Ray::new(point!(-1.5, 0, -5), vector!(0, 0, 1));
+        let before: Vec<f64> = c.intersect(&r).iter().map(|i| i.t).collect();
+        c.divide(1);
+        let after: Vec<f64> = c.intersect(&r).iter().map(|i| i.t).collect();
+        assert_eq!(before, after);
+        assert_eq!(before, vec![4.0, 6.0]);
+    }
+
+    #[test]
+    fn nested_csg_and_groups() {
+        // The union of a group and a sphere, subtracted from a cube
+        let mut g = Group::new();
+        let mut s1 = Sphere::new();
+        s1.set_transform(translation(0.0, 0.0, -0.5));
+        g.add_child(Box::new(s1));
+        let union = Csg::new(CsgOperation::Union, Box::new(g), Box::new(Sphere::new()));
+        assert!(union.includes(union.left()));
+
+        let c = Csg::new(
+            CsgOperation::Intersection,
+            Box::new(Cube::new()),
+            Box::new(union),
+        );
+        let r = Ray::new(point!(0, 0, -5), vector!(0, 0, 1));
+        let xs = c.intersect(&r);
+        // The union spans from -1.5 to 1 in z, clipped by the cube
+        assert_eq!(xs.len(), 2);
+        assert_eq!(xs[0].t, 4.0);
+        assert_eq!(xs[1].t, 6.0);
+        let comps = xs[0].prepare_computations(&r, &xs);
+        assert!(comps.normalv == vector!(0, 0, -1));
+    }
+}
@@ -0,0 +1,137 @@
+use crate::bounds::{check_axis, Bounds};
+use crate::intersection::{Intersection, Intersections};
+use crate::ray::Ray;
+use crate::shapes::{Shape, ShapeData};
+use crate::tuple::{Point, Tuple, Vector};
+
+/// An axis-aligned cube centered at the origin, spanning from -1 to 1
+/// along every axis in its object space
+#[derive(Clone, Debug)]
+pub struct Cube {
+    data: ShapeData,
+}
+
+impl Cube {
+    pub fn new() -> Cube {
+        Cube {
+            data: ShapeData::new(),
+        }
+    }
+}
+
+impl Default for Cube {
+    fn default() -> Self {
+        Cube::new()
+    }
+}
+
+impl Shape for Cube {
+    fn data(&self) -> &ShapeData {
+        &self.data
+    }
+
+    fn data_mut(&mut self) -> &mut ShapeData {
+        &mut self.data
+    }
+
+    /// Intersect the three pairs of slabs that make up the `Cube`, the
+    /// `Ray` only hits it if all of them overlap
+    fn local_intersect(&self, ray: &Ray) -> Intersections<'_> {
+        let (xtmin, xtmax) = check_axis(ray.origin.x, ray.direction.x, -1.0, 1.0);
+        let (ytmin, ytmax) = check_axis(ray.origin.y, ray.direction.y, -1.0, 1.0);
+        let (ztmin, ztmax) = check_axis(ray.origin.z, ray.direction.z, -1.0, 1.0);
+
+        let tmin = xtmin.max(ytmin).max(ztmin);
+        let tmax = xtmax.min(ytmax).min(ztmax);
+
+        if tmin > tmax {
+            return Intersections::default();
+        }
+
+        Intersections::new(vec![
+            Intersection::new(tmin, self),
+            Intersection::new(tmax, self),
+        ])
+    }
+
+    /// The normal points along the axis of the largest component of
+    /// `point`, which tells which face it lies on
+    fn local_normal_at(&self, point: Point, _hit: &Intersection) -> Vector {
+        let (absx, absy, absz) = (point.x.abs(), point.y.abs(), point.z.abs());
+        let maxc = absx.max(absy).max(absz);
+
+        if maxc == absx {
+            Vector::new(point.x, 0.0, 0.0)
+        } else if maxc == absy {
+            Vector::new(0.0, point.y, 0.0)
+        } else {
+            Vector::new(0.0, 0.0, point.z)
+        }
+    }
+
+    fn bounds(&self) -> Bounds {
+        Bounds::new(Point::new(-1.0, -1.0, -1.0), Point::new(1.0, 1.0, 1.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{point, vector};
+
+    #[test]
+    fn ray_intersects_cube() {
+        let c = Cube::new();
+        let cases = [
+            (point!(5, 0.5, 0), vector!(-1, 0, 0), 4.0, 6.0),
+            (point!(-5, 0.5, 0), vector!(1, 0, 0), 4.0, 6.0),
+            (point!(0.5, 5, 0), vector!(0, -1, 0), 4.0, 6.0),
+            (point!(0.5, -5, 0), vector!(0, 1, 0), 4.0, 6.0),
+            (point!(0.5, 0, 5), vector!(0, 0, -1), 4.0, 6.0),
+            (point!(0.5, 0, -5), vector!(0, 0, 1), 4.0, 6.0),
+            (point!(0, 0.5, 0), vector!(0, 0, 1), -1.0, 1.0),
+        ];
+        for (origin, direction, t1, t2) in cases {
+            let xs = c.local_intersect(&Ray::new(origin, direction));
+            assert_eq!(xs.len(), 2);
+            assert_eq!(xs[0].t, t1);
+            assert_eq!(xs[1].t, t2);
+        }
+    }
+
+    #[test]
+    fn ray_misses_cube() {
+        let c = Cube::new();
+        let cases = [
+            (point!(-2, 0, 0), vector!(0.2673, 0.5345, 0.8018)),
+            (point!(0, -2, 0), vector!(0.8018, 0.2673, 0.5345)),
+            (point!(0, 0, -2), vector!(0.5345, 0.8018, 0.2673)),
+            (point!(2, 0, 2), vector!(0, 0, -1)),
+            (point!(0, 2, 2), vector!(0, -1, 0)),
+            (point!(2, 2, 0), vector!(-1, 0, 0)),
+        ];
+        for (origin, direction) in cases {
+            let xs = c.local_intersect(&Ray::new(origin, direction));
+            assert!(xs.is_empty());
+        }
+    }
+
+    #[test]
+    fn normal_on_surface_of_cube() {
+        let c = Cube::new();
+        let cases = [
+            (point!(1, 0.5, -0.8), vector!(1, 0, 0)),
+            (point!(-1, -0.2, 0.9), vector!(-1, 0, 0)),
+            (point!(-0.4, 1, -0.1), vector!(0, 1, 0)),
+            (point!(0.3, -1, -0.7), vector!(0, -1, 0)),
+            (point!(-0.6, 0.3, 1), vector!(0, 0, 1)),
+            (point!(0.4, 0.4, -1), vector!(0, 0, -1)),
+            (point!(1, 1, 1), vector!(1, 0, 0)),
+            (point!(-1, -1, -1), vector!(-1, 0, 0)),
+        ];
+        let hit = Intersection::new(0.0, &c);
+        for (point, normal) in cases {
+            assert!(c.local_normal_at(point, &hit) == normal);
+        }
+    }
+}
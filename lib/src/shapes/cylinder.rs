@@ -0,0 +1,247 @@
+use crate::bounds::Bounds;
+use crate::intersection::{Intersection, Intersections};
+use crate::ray::Ray;
+use crate::shapes::{Shape, ShapeData};
+use crate::tuple::utils::EPSILON;
+use crate::tuple::{Point, Tuple, Vector};
+
+/// A cylinder of radius 1 around the y axis in its object space
+///
+/// The cylinder is infinite unless it is truncated with `minimum` and
+/// `maximum`, and it is only capped at those ends if `closed` is set.
+#[derive(Clone, Debug)]
+pub struct Cylinder {
+    data: ShapeData,
+    pub minimum: f64,
+    pub maximum: f64,
+    pub closed: bool,
+}
+
+impl Cylinder {
+    /// Create an infinite, open `Cylinder`
+    pub fn new() -> Cylinder {
+        Cylinder {
+            data: ShapeData::new(),
+            minimum: f64::NEG_INFINITY,
+            maximum: f64::INFINITY,
+            closed: false,
+        }
+    }
+
+    fn intersect_caps<'a>(&'a self, ray: &Ray, xs: &mut Vec<Intersection<'a>>) {
+        // Caps only matter if the cylinder is closed and could be hit
+        if !self.closed || ray.direction.y.abs() < EPSILON {
+            return;
+        }
+
+        for y in [self.minimum, self.maximum] {
+            let t = (y - ray.origin.y) / ray.direction.y;
+            if check_cap(ray, t, 1.0) {
+                xs.push(Intersection::new(t, self));
+            }
+        }
+    }
+}
+
+impl Default for Cylinder {
+    fn default() -> Self {
+        Cylinder::new()
+    }
+}
+
+/// Check if the intersection at `t` is within `radius` from the y axis,
+/// i.e. if it lies on a cap
+pub(crate) fn check_cap(ray: &Ray, t: f64, radius: f64) -> bool {
+    let x = ray.origin.x + t * ray.direction.x;
+    let z = ray.origin.z + t * ray.direction.z;
+
+    x * x + z * z <= radius * radius + EPSILON
+}
+
+impl Shape for Cylinder {
+    fn data(&self) -> &ShapeData {
+        &self.data
+    }
+
+    fn data_mut(&mut self) -> &mut ShapeData {
+        &mut self.data
+    }
+
+    fn local_intersect(&self, ray: &Ray) -> Intersections<'_> {
+        let mut xs = Vec::new();
+        let a = ray.direction.x.powi(2) + ray.direction.z.powi(2);
+
+        // A ray parallel to the y axis can only hit the caps
+        if a.abs() >= EPSILON {
+            let b = 2.0 * ray.origin.x * ray.direction.x + 2.0 * ray.origin.z * ray.direction.z;
+            let c = ray.origin.x.powi(2) + ray.origin.z.powi(2) - 1.0;
+            let discriminant = b * b - 4.0 * a * c;
+
+            if discriminant < 0.0 {
+                return Intersections::default();
+            }
+
+            let sqrt_disc = discriminant.sqrt();
+            let t0 = (-b - sqrt_disc) / (2.0 * a);
+            let t1 = (-b + sqrt_disc) / (2.0 * a);
+
+            for t in [t0, t1] {
+                let y = ray.origin.y + t * ray.direction.y;
+                if self.minimum < y && y < self.maximum {
+                    xs.push(Intersection::new(t, self));
+                }
+            }
+        }
+
+        self.intersect_caps(ray, &mut xs);
+        Intersections::new(xs)
+    }
+
+    fn local_normal_at(&self, point: Point, _hit: &Intersection) -> Vector {
+        let distance = point.x.powi(2) + point.z.powi(2);
+
+        if distance < 1.0 && point.y >= self.maximum - EPSILON {
+            Vector::new(0.0, 1.0, 0.0)
+        } else if distance < 1.0 && point.y <= self.minimum + EPSILON {
+            Vector::new(0.0, -1.0, 0.0)
+        } else {
+            Vector::new(point.x, 0.0, point.z)
+        }
+    }
+
+    fn bounds(&self) -> Bounds {
+        Bounds::new(
+            Point::new(-1.0, self.minimum, -1.0),
+            Point::new(1.0, self.maximum, 1.0),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{point, vector};
+
+    #[test]
+    fn ray_misses_cylinder() {
+        let cyl = Cylinder::new();
+        let cases = [
+            (point!(1, 0, 0), vector!(0, 1, 0)),
+            (point!(0, 0, 0), vector!(0, 1, 0)),
+            (point!(0, 0, -5), vector!(1, 1, 1)),
+        ];
+        for (origin, direction) in cases {
+            let r = Ray::new(origin, direction.normalize());
+            assert!(cyl.local_intersect(&r).is_empty());
+        }
+    }
+
+    #[test]
+    fn ray_strikes_cylinder() {
+        let cyl = Cylinder::new();
+        let cases = [
+            (point!(1, 0, -5), vector!(0, 0, 1), 5.0, 5.0),
+            (point!(0, 0, -5), vector!(0, 0, 1), 4.0, 6.0),
+            (point!(0.5, 0, -5), vector!(0.1, 1, 1), 6.80798, 7.08872),
+        ];
+        for (origin, direction, t0, t1) in cases {
+            let r = Ray::new(origin, direction.normalize());
+            let xs = cyl.local_intersect(&r);
+            assert_eq!(xs.len(), 2);
+            assert!((xs[0].t - t0).abs() < 1e-5);
+            assert!((xs[1].t - t1).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn normal_on_cylinder() {
+        let cyl = Cylinder::new();
+        let cases = [
+            (point!(1, 0, 0), vector!(1, 0, 0)),
+            (point!(0, 5, -1), vector!(0, 0, -1)),
+            (point!(0, -2, 1), vector!(0, 0, 1)),
+            (point!(-1, 1, 0), vector!(-1, 0, 0)),
+        ];
+        let hit = Intersection::new(0.0, &cyl);
+        for (point, normal) in cases {
+            assert!(cyl.local_normal_at(point, &hit) == normal);
+        }
+    }
+
+    #[test]
+    fn default_cylinder_is_infinite_and_open() {
+        let cyl = Cylinder::new();
+        assert_eq!(cyl.minimum, f64::NEG_INFINITY);
+        assert_eq!(cyl.maximum, f64::INFINITY);
+        assert!(!cyl.closed);
+        assert!(!cyl.bounds().is_finite());
+    }
+
+    #[test]
+    fn intersect_truncated_cylinder() {
+        let mut cyl = Cylinder::new();
+        cyl.minimum = 1.0;
+        cyl.maximum = 2.0;
+        let cases = [
+            (point!(0, 1.5, 0), vector!(0.1, 1, 0), 0),
+            (point!(0, 3, -5), vector!(0, 0, 1), 0),
+            (point!(0, 0, -5), vector!(0, 0, 1), 0),
+            (point!(0, 2, -5), vector!(0, 0, 1), 0),
+            (point!(0, 1, -5), vector!(0, 0, 1), 0),
+            (point!(0, 1.5, -2), vector!(0, 0, 1), 2),
+        ];
+        for (origin, direction, count) in cases {
+            let r = Ray::new(origin, direction.normalize());
+            assert_eq!(cyl.local_intersect(&r).len(), count);
+        }
+    }
+
+    #[test]
+    fn intersect_caps_of_closed_cylinder() {
+        let mut cyl = Cylinder::new();
+        cyl.minimum = 1.0;
+        cyl.maximum = 2.0;
+        cyl.closed = true;
+        let cases = [
+            (point!(0, 3, 0), vector!(0, -1, 0), 2),
+            (point!(0, 3, -2), vector!(0, -1, 2), 2),
+            (point!(0, 4, -2), vector!(0, -1, 1), 2),
+            (point!(0, 0, -2), vector!(0, 1, 2), 2),
+            (point!(0, -1, -2), vector!(0, 1, 1), 2),
+        ];
+        for (origin, direction, count) in cases {
+            let r = Ray::new(origin, direction.normalize());
+            assert_eq!(cyl.local_intersect(&r).len(), count);
+        }
+    }
+
+    #[test]
+    fn normal_on_end_caps() {
+        let mut cyl = Cylinder::new();
+        cyl.minimum = 1.0;
+        cyl.maximum = 2.0;
+        cyl.closed = true;
+        let cases = [
+            (point!(0, 1, 0), vector!(0, -1, 0)),
+            (point!(0.5, 1, 0), vector!(0, -1, 0)),
+            (point!(0, 1, 0.5), vector!(0, -1, 0)),
+            (point!(0, 2, 0), vector!(0, 1, 0)),
+            (point!(0.5, 2, 0), vector!(0, 1, 0)),
+            (point!(0, 2, 0.5), vector!(0, 1, 0)),
+        ];
+        let hit = Intersection::new(0.0, &cyl);
+        for (point, normal) in cases {
+            assert!(cyl.local_normal_at(point, &hit) == normal);
+        }
+    }
+
+    #[test]
+    fn truncated_cylinder_bounds() {
+        let mut cyl = Cylinder::new();
+        cyl.minimum = -5.0;
+        cyl.maximum = 3.0;
+        let b = cyl.bounds();
+        assert!(b.min == point!(-1, -5, -1));
+        assert!(b.max == point!(1, 3, 1));
+    }
+}
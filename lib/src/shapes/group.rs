@@ -0,0 +1,512 @@
+use crate::bounds::Bounds;
+use crate::intersection::{Intersection, Intersections};
+use crate::ray::Ray;
+use crate::shapes::{children_parent_inverse, Shape, ShapeData};
+use crate::tuple::{Point, Vector};
+use std::cell::Cell;
+
+type Children = Vec<Box<dyn Shape>>;
+
+/// A collection of shapes that are transformed as a single unit
+///
+/// The transformation of the `Group` is applied on top of the
+/// transformation of each child.
+#[derive(Debug)]
+pub struct Group {
+    data: ShapeData,
+    children: Children,
+    // Cached, in the object space of the group, `None` once a child
+    // may have been changed through `children_mut`
+    bounds: Cell<Option<Bounds>>,
+}
+
+impl Group {
+    /// Create an empty `Group`
+    pub fn new() -> Group {
+        Group {
+            data: ShapeData::new(),
+            children: Vec::new(),
+            bounds: Cell::new(Some(Bounds::from_points(&[]))),
+        }
+    }
+
+    pub fn children(&self) -> &[Box<dyn Shape>] {
+        &self.children
+    }
+
+    /// Add `child` to the `Group`
+    ///
+    /// The child should already have its own transformation, it is
+    /// positioned relative to the `Group` from then on. It stops
+    /// casting shadows if the `Group` doesn't cast any.
+    pub fn add_child(&mut self, mut child: Box<dyn Shape>) {
+        child.set_parent_inverse(children_parent_inverse(self));
+        if !self.casts_shadow() {
+            child.set_casts_shadow(false);
+        }
+        if let Some(bounds) = self.bounds.get() {
+            let child_bounds = child.bounds().transform(child.transform());
+            self.bounds.set(Some(bounds.merge(&child_bounds)));
+        }
+        self.children.push(child);
+    }
+
+    /// Move the children that fit entirely in one half of the
+    /// `Bounds` out of the `Group`, the others stay where they are
+    ///
+    /// Only the children with finite `Bounds` are split, a single
+    /// `Plane` would otherwise make both halves infinite.
+    fn partition_children(&mut self) -> (Children, Children) {
+        let finite_bounds = self
+            .children
+            .iter()
+            .map(|child| child.bounds().transform(child.transform()))
+            .filter(|bounds| bounds.is_finite())
+            .fold(Bounds::from_points(&[]), |all, bounds| all.merge(&bounds));
+        let (left_bounds, right_bounds) = finite_bounds.split();
+        let mut left = Vec::new();
+        let mut right = Vec::new();
+        let mut remaining = Vec::new();
+
+        for child in self.children.drain(..) {
+            let bounds = child.bounds().transform(child.transform());
+            if left_bounds.contains_bounds(&bounds) {
+                left.push(child);
+            } else if right_bounds.contains_bounds(&bounds) {
+                right.push(child);
+            } else {
+                remaining.push(child);
+            }
+        }
+        self.children = remaining;
+
+        (left, right)
+    }
+
+    /// Add a new `Group` made of `children`, they are still positioned
+    /// the same way relative to this `Group`
+    fn make_subgroup(&mut self, children: Children) {
+        let mut subgroup = Group::new();
+        for child in children {
+            subgroup.add_child(child);
+        }
+        self.add_child(Box::new(subgroup));
+    }
+}
+
+impl Default for Group {
+    fn default() -> Self {
+        Group::new()
+    }
+}
+
+impl Shape for Group {
+    fn data(&self) -> &ShapeData {
+        &self.data
+    }
+
+    fn data_mut(&mut self) -> &mut ShapeData {
+        &mut self.data
+    }
+
+    /// The children can be moved through the returned references, so
+    /// the cached `Bounds` are computed again the next time they are
+    /// needed
+    fn children_mut(&mut self) -> Vec<&mut Box<dyn Shape>> {
+        self.bounds.set(None);
+        self.children.iter_mut().collect()
+    }
+
+    fn includes(&self, other: &dyn Shape) -> bool {
+        std::ptr::addr_eq(self, other) || self.children.iter().any(|child| child.includes(other))
+    }
+
+    /// Recursively move children into subgroups, splitting the
+    /// `Bounds` in halves until a `Group` has fewer than `threshold`
+    /// children
+    ///
+    /// Children that straddle both halves stay in the `Group` itself,
+    /// and so do shapes with infinite `Bounds` such as a `Plane`, the
+    /// halves are computed from the other children.
+    fn divide(&mut self, threshold: usize) {
+        if threshold <= self.children.len() {
+            let count = self.children.len();
+            let (left, right) = self.partition_children();
+            if left.len() == count || right.len() == count {
+                // Every child is in the same place, splitting again
+                // would never end
+                self.children = left.into_iter().chain(right).collect();
+            } else {
+                if !left.is_empty() {
+                    self.make_subgroup(left);
+                }
+                if !right.is_empty() {
+                    self.make_subgroup(right);
+                }
+            }
+        }
+
+        for child in self.children.iter_mut() {
+            child.divide(threshold);
+        }
+    }
+
+    /// Intersect every child, the `ray` is already in the space of the
+    /// `Group` which is the parent space of the children
+    ///
+    /// Nothing is tested when the `ray` misses the `Bounds` of the
+    /// `Group`.
+    fn local_intersect(&self, ray: &Ray) -> Intersections<'_> {
+        if !self.bounds().intersects(ray) {
+            return Intersections::default();
+        }
+
+        let mut xs = Vec::new();
+        for child in &self.children {
+            xs.extend(child.intersect(ray));
+        }
+
+        Intersections::new(xs)
+    }
+
+    fn local_normal_at(&self, _point: Point, _hit: &Intersection) -> Vector {
+        unreachable!("the normal of a group is computed by its children")
+    }
+
+    /// The `Bounds` of every child, transformed into the space of the
+    /// `Group`
+    fn bounds(&self) -> Bounds {
+        if let Some(bounds) = self.bounds.get() {
+            return bounds;
+        }
+
+        let bounds = self
+            .children
+            .iter()
+            .map(|child| child.bounds().transform(child.transform()))
+            .fold(Bounds::from_points(&[]), |all, bounds| all.merge(&bounds));
+        self.bounds.set(Some(bounds));
+        bounds
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shapes::tests::TestShape;
+    use crate::shapes::{Cube, Cylinder, Plane, Sphere};
+    use crate::transform::{rotation_y, scaling, translation};
+    use crate::tuple::{Matrix4, Tuple};
+    use crate::{point, vector};
+    use std::f64::consts::FRAC_PI_2;
+    use std::rc::Rc;
+
+    #[test]
+    fn create_group() {
+        let g = Group::new();
+        assert!(*g.transform() == Matrix4::identity());
+        assert!(g.children().is_empty());
+    }
+
+    #[test]
+    fn add_child_to_group() {
+        let mut g = Group::new();
+        g.set_transform(translation(1.0, 0.0, 0.0));
+        g.add_child(Box::new(TestShape::default()));
+        assert_eq!(g.children().len(), 1);
+        // The child now knows about the transformation of its parent
+        assert!(*g.children()[0].parent_inverse() == translation(-1.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn intersect_empty_group() {
+        let g = Group::new();
+        let r = Ray::new(point!(0, 0, 0), vector!(0, 0, 1));
+        assert!(g.local_intersect(&r).is_empty());
+    }
+
+    #[test]
+    fn intersect_nonempty_group() {
+        let mut g = Group::new();
+        let s1 = Sphere::new();
+        let mut s2 = Sphere::new();
+        s2.set_transform(translation(0.0, 0.0, -3.0));
+        let mut s3 = Sphere::new();
+        s3.set_transform(translation(5.0, 0.0, 0.0));
+        g.add_child(Box::new(s1));
+        g.add_child(Box::new(s2));
+        g.add_child(Box::new(s3));
+
+        let r = Ray::new(point!(0, 0, -5), vector!(0, 0, 1));
+        let xs = g.local_intersect(&r);
+        assert_eq!(xs.len(), 4);
+        let children = g.children();
+        assert!(std::ptr::addr_eq(xs[0].object, children[1].as_ref()));
+        assert!(std::ptr::addr_eq(xs[1].object, children[1].as_ref()));
+        assert!(std::ptr::addr_eq(xs[2].object, children[0].as_ref()));
+        assert!(std::ptr::addr_eq(xs[3].object, children[0].as_ref()));
+    }
+
+    #[test]
+    fn intersect_transformed_group() {
+        let mut g = Group::new();
+        g.set_transform(scaling(2.0, 2.0, 2.0));
+        let mut s = Sphere::new();
+        s.set_transform(translation(5.0, 0.0, 0.0));
+        g.add_child(Box::new(s));
+
+        let r = Ray::new(point!(10, 0, -10), vector!(0, 0, 1));
+        assert_eq!(g.intersect(&r).len(), 2);
+    }
+
+    /// A sphere inside a group inside another group, which ends up
+    /// somewhere along the negative z axis
+    fn nested_groups(inner_transform: Matrix4) -> Group {
+        let mut s = Sphere::new();
+        s.set_transform(translation(5.0, 0.0, 0.0));
+        let mut g2 = Group::new();
+        g2.set_transform(inner_transform);
+        g2.add_child(Box::new(s));
+
+        let mut g1 = Group::new();
+        g1.set_transform(rotation_y(FRAC_PI_2));
+        g1.add_child(Box::new(g2));
+        g1
+    }
+
+    /// Find the nested sphere, the only object that can be hit
+    fn nested_sphere(g1: &Group) -> &dyn Shape {
+        let r = Ray::new(point!(0, 0, 0), vector!(0, 0, -1));
+        g1.intersect(&r)[0].object
+    }
+
+    #[test]
+    fn convert_point_from_world_to_object_space() {
+        let g1 = nested_groups(scaling(2.0, 2.0, 2.0));
+        let s = nested_sphere(&g1);
+        let p = s.world_to_object(point!(-2, 0, -10));
+        assert!(p == point!(0, 0, -1));
+    }
+
+    #[test]
+    fn convert_normal_from_object_to_world_space() {
+        let g1 = nested_groups(scaling(1.0, 2.0, 3.0));
+        let s = nested_sphere(&g1);
+        let a = 3.0_f64.sqrt() / 3.0;
+        let n = s.normal_to_world(vector!(a, a, a));
+        assert!((n.x - 0.2857).abs() < 1e-4);
+        assert!((n.y - 0.4286).abs() < 1e-4);
+        assert!((n.z + 0.8571).abs() < 1e-4);
+    }
+
+    #[test]
+    fn normal_on_child_object() {
+        let g1 = nested_groups(scaling(1.0, 2.0, 3.0));
+        let s = nested_sphere(&g1);
+        let hit = Intersection::new(0.0, s);
+        let n = s.normal_at(point!(1.7321, 1.1547, -5.5774), &hit);
+        assert!((n.x - 0.2857).abs() < 1e-4);
+        assert!((n.y - 0.4286).abs() < 1e-4);
+        assert!((n.z + 0.8571).abs() < 1e-4);
+    }
+
+    #[test]
+    fn transform_group_after_adding_children() {
+        let mut g = Group::new();
+        g.add_child(Box::new(Sphere::new()));
+        g.set_transform(translation(0.0, 0.0, 5.0));
+        let r = Ray::new(point!(0, 0, 0), vector!(0, 0, 1));
+        let xs = g.intersect(&r);
+        assert_eq!(xs[0].t, 4.0);
+        let comps = xs[0].prepare_computations(&r, &xs);
+        assert!(comps.normalv == vector!(0, 0, -1));
+    }
+
+    #[test]
+    fn group_includes_its_descendants() {
+        let g1 = nested_groups(scaling(2.0, 2.0, 2.0));
+        let s = nested_sphere(&g1);
+        assert!(g1.includes(s));
+        assert!(g1.children()[0].includes(s));
+        assert!(!g1.includes(&Sphere::new()));
+    }
+
+    #[test]
+    fn group_bounds_contain_children() {
+        let mut s = Sphere::new();
+        s.set_transform(translation(2.0, 5.0, -3.0) * scaling(2.0, 2.0, 2.0));
+        let mut c = Cylinder::new();
+        c.minimum = -2.0;
+        c.maximum = 2.0;
+        c.set_transform(translation(-4.0, -1.0, 4.0) * scaling(0.5, 1.0, 0.5));
+        let mut g = Group::new();
+        g.add_child(Box::new(s));
+        g.add_child(Box::new(c));
+
+        let b = g.bounds();
+        assert!(b.min == point!(-4.5, -3, -5));
+        assert!(b.max == point!(4, 7, 4.5));
+    }
+
+    #[test]
+    fn children_follow_shadow_casting_of_group() {
+        let mut g = Group::new();
+        g.add_child(Box::new(Sphere::new()));
+        g.set_casts_shadow(false);
+        g.add_child(Box::new(Sphere::new()));
+        assert!(g.children().iter().all(|child| !child.casts_shadow()));
+
+        g.set_casts_shadow(true);
+        assert!(g.children().iter().all(|child| child.casts_shadow()));
+    }
+
+    #[test]
+    fn empty_child_group_keeps_bounds_finite() {
+        let mut inner = Group::new();
+        inner.set_transform(rotation_y(FRAC_PI_2));
+        let mut g = Group::new();
+        g.add_child(Box::new(inner));
+        assert!(g.bounds().is_empty());
+
+        g.add_child(Box::new(Sphere::new()));
+        let b = g.bounds();
+        assert!(b.min == point!(-1, -1, -1));
+        assert!(b.max == point!(1, 1, 1));
+    }
+
+    #[test]
+    fn moved_child_is_still_hit() {
+        let mut g = Group::new();
+        g.add_child(Box::new(Sphere::new()));
+        g.children_mut()[0].set_transform(translation(10.0, 0.0, 0.0));
+        assert!(g.bounds().max == point!(11, 1, 1));
+
+        let r = Ray::new(point!(10, 0, -5), vector!(0, 0, 1));
+        assert_eq!(g.intersect(&r).len(), 2);
+    }
+
+    #[test]
+    fn intersect_skips_children_when_bounds_are_missed() {
+        let child = TestShape::default();
+        let saved_ray = Rc::clone(&child.saved_ray);
+        let mut g = Group::new();
+        g.add_child(Box::new(child));
+        let r = Ray::new(point!(0, 0, -5), vector!(0, 1, 0));
+        g.intersect(&r);
+        assert!(saved_ray.get().is_none());
+
+        let r = Ray::new(point!(0, 0, -5), vector!(0, 0, 1));
+        g.intersect(&r);
+        assert!(saved_ray.get().is_some());
+    }
+
+    /// Three spheres: two side by side along x and one in the middle
+    fn three_spheres() -> Group {
+        let mut s1 = Sphere::new();
+        s1.set_transform(translation(-2.0, 0.0, 0.0));
+        let mut s2 = Sphere::new();
+        s2.set_transform(translation(2.0, 0.0, 0.0));
+        let s3 = Sphere::new();
+        let mut g = Group::new();
+        g.add_child(Box::new(s1));
+        g.add_child(Box::new(s2));
+        g.add_child(Box::new(s3));
+        g
+    }
+
+    #[test]
+    fn partition_children_of_group() {
+        let mut g = three_spheres();
+        let (left, right) = g.partition_children();
+        assert_eq!(g.children().len(), 1);
+        assert_eq!(left.len(), 1);
+        assert_eq!(right.len(), 1);
+        assert!(*left[0].transform() == translation(-2.0, 0.0, 0.0));
+        assert!(*right[0].transform() == translation(2.0, 0.0, 0.0));
+        assert!(*g.children()[0].transform() == Matrix4::identity());
+    }
+
+    #[test]
+    fn make_subgroup_from_children() {
+        let mut g = Group::new();
+        g.make_subgroup(vec![Box::new(Sphere::new()), Box::new(Cube::new())]);
+        assert_eq!(g.children().len(), 1);
+        let r = Ray::new(point!(0, 0, -5), vector!(0, 0, 1));
+        assert_eq!(g.intersect(&r).len(), 4);
+    }
+
+    #[test]
+    fn divide_group_into_subgroups() {
+        let mut g = three_spheres();
+        g.divide(1);
+        // The middle sphere stays, the others are moved to subgroups
+        assert_eq!(g.children().len(), 3);
+        assert!(*g.children()[0].transform() == Matrix4::identity());
+        let r = Ray::new(point!(-2, 0, -5), vector!(0, 0, 1));
+        let xs = g.intersect(&r);
+        assert_eq!(xs.len(), 2);
+        assert!(xs[0].object.transform() == &translation(-2.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn divide_group_with_an_infinite_child() {
+        let mut g = Group::new();
+        for x in 0..100 {
+            let mut s = Sphere::new();
+            s.set_transform(translation(x as f64 * 3.0, 0.0, 0.0));
+            g.add_child(Box::new(s));
+        }
+        let mut floor = Plane::new();
+        floor.set_transform(translation(0.0, -1.0, 0.0));
+        g.add_child(Box::new(floor));
+        g.divide(4);
+
+        // The plane stays, the spheres go in the two subgroups
+        assert_eq!(g.children().len(), 3);
+        assert!(!g.children()[0].bounds().is_finite());
+        let r = Ray::new(point!(99, 0, -5), vector!(0, 0, 1));
+        let xs = g.intersect(&r);
+        assert_eq!(xs.len(), 2);
+        assert_eq!(xs[0].t, 4.0);
+        let r = Ray::new(point!(1.5, 5, 0), vector!(0, -1, 0));
+        let xs = g.intersect(&r);
+        assert_eq!(xs.len(), 1);
+        assert_eq!(xs[0].t, 6.0);
+    }
+
+    #[test]
+    fn divide_group_with_too_few_children() {
+        let mut g = three_spheres();
+        g.divide(4);
+        assert_eq!(g.children().len(), 3);
+    }
+
+    #[test]
+    fn divide_group_of_identical_children() {
+        let mut g = Group::new();
+        for _ in 0..4 {
+            g.add_child(Box::new(Sphere::new()));
+        }
+        g.divide(1);
+        assert_eq!(g.children().len(), 4);
+    }
+
+    #[test]
+    fn divided_group_keeps_its_transformation() {
+        let mut g = Group::new();
+        g.set_transform(scaling(2.0, 2.0, 2.0));
+        for x in 0..10 {
+            let mut s = Sphere::new();
+            s.set_transform(translation(x as f64 * 3.0, 0.0, 0.0) * scaling(0.5, 0.5, 0.5));
+            g.add_child(Box::new(s));
+        }
+        g.divide(2);
+
+        let r = Ray::new(point!(54, 0, -5), vector!(0, 0, 1));
+        let xs = g.intersect(&r);
+        assert_eq!(xs.len(), 2);
+        assert_eq!(xs[0].t, 4.0);
+        let comps = xs[0].prepare_computations(&r, &xs);
+        assert!(comps.normalv == vector!(0, 0, -1));
+    }
+}
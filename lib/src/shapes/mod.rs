@@ -0,0 +1,345 @@
+// Exports
+pub mod bvh;
+pub mod cone;
+pub mod csg;
+pub mod cube;
+pub mod cylinder;
+pub mod group;
+pub mod plane;
+pub mod smooth_triangle;
+pub mod sphere;
+pub mod triangle;
+
+// Imports
+use crate::bounds::Bounds;
+use crate::intersection::{Intersection, Intersections};
+use crate::material::Material;
+use crate::ray::Ray;
+use crate::tuple::{Matrix4, Point, Vector};
+pub use bvh::{Bvh, BvhStats};
+pub use cone::Cone;
+pub use csg::{Csg, CsgOperation};
+pub use cube::Cube;
+pub use cylinder::Cylinder;
+pub use group::Group;
+pub use plane::Plane;
+pub use smooth_triangle::SmoothTriangle;
+pub use sphere::Sphere;
+use std::fmt::Debug;
+pub use triangle::Triangle;
+
+/// The state that every `Shape` carries, regardless of its geometry
+#[derive(Clone, Debug)]
+pub struct ShapeData {
+    transform: Matrix4,
+    // Cached so that it is not computed for every ray
+    inverse: Matrix4,
+    material: Material,
+    casts_shadow: bool,
+    // The inverse transformations of every `Group` above the shape,
+    // composed into a single matrix
+    parent_inverse: Matrix4,
+}
+
+impl ShapeData {
+    pub fn new() -> ShapeData {
+        ShapeData {
+            transform: Matrix4::identity(),
+            inverse: Matrix4::identity(),
+            material: Material::new(),
+            casts_shadow: true,
+            parent_inverse: Matrix4::identity(),
+        }
+    }
+
+    /// Change the transformation, caching its inverse
+    ///
+    /// # Panics
+    /// If `transform` is not invertible.
+    pub fn set_transform(&mut self, transform: Matrix4) {
+        self.inverse = transform
+            .inverse()
+            .expect("the transformation of a shape must be invertible");
+        self.transform = transform;
+    }
+}
+
+impl Default for ShapeData {
+    fn default() -> Self {
+        ShapeData::new()
+    }
+}
+
+/// The `Shape` trait is implemented by every primitive that can be
+/// rendered.
+///
+/// Implementors only deal with their geometry in object space, through
+/// `local_intersect` and `local_normal_at`; converting between world
+/// space and object space is done once, here.
+pub trait Shape: Debug {
+    fn data(&self) -> &ShapeData;
+    fn data_mut(&mut self) -> &mut ShapeData;
+
+    /// Intersect a `Ray` that is already in object space
+    fn local_intersect(&self, ray: &Ray) -> Intersections<'_>;
+
+    /// The normal at a `Point` that is already in object space, `hit`
+    /// is the `Intersection` that produced it
+    ///
+    /// # Panics
+    /// For shapes made of other shapes, which have no surface of their
+    /// own: the normal is always computed by the child that was hit.
+    fn local_normal_at(&self, point: Point, hit: &Intersection) -> Vector;
+
+    /// The `Bounds` of the shape in object space
+    fn bounds(&self) -> Bounds;
+
+    fn transform(&self) -> &Matrix4 {
+        &self.data().transform
+    }
+
+    fn inverse(&self) -> &Matrix4 {
+        &self.data().inverse
+    }
+
+    /// Change the transformation of the shape
+    ///
+    /// # Panics
+    /// If `transform` is not invertible.
+    fn set_transform(&mut self, transform: Matrix4) {
+        self.data_mut().set_transform(transform);
+        update_children(self);
+    }
+
+    fn material(&self) -> &Material {
+        &self.data().material
+    }
+
+    fn set_material(&mut self, material: Material) {
+        self.data_mut().material = material;
+    }
+
+    /// Whether the shape blocks the light that reaches other shapes
+    fn casts_shadow(&self) -> bool {
+        self.data().casts_shadow
+    }
+
+    /// Change whether the shape casts shadows, for shapes made of
+    /// other shapes every child is changed as well
+    fn set_casts_shadow(&mut self, casts_shadow: bool) {
+        self.data_mut().casts_shadow = casts_shadow;
+        for child in self.children_mut() {
+            child.set_casts_shadow(casts_shadow);
+        }
+    }
+
+    /// The inverse transformations of every `Group` above the shape,
+    /// which take a world space `Point` to the space of its parent
+    fn parent_inverse(&self) -> &Matrix4 {
+        &self.data().parent_inverse
+    }
+
+    /// Change the inverse transformations of the groups above the
+    /// shape, this is done by a `Group` when the shape is added to it
+    fn set_parent_inverse(&mut self, parent_inverse: Matrix4) {
+        self.data_mut().parent_inverse = parent_inverse;
+        update_children(self);
+    }
+
+    /// The shapes this one is made of, which are kept in its object
+    /// space
+    ///
+    /// Primitives have none, shapes like a `Group` must return every
+    /// child so that changes to the transformations reach them. The
+    /// children may be moved through the references, so anything
+    /// cached about them must be computed again afterwards.
+    fn children_mut(&mut self) -> Vec<&mut Box<dyn Shape>> {
+        Vec::new()
+    }
+
+    /// Check if `other` is this shape, or one of its descendants
+    fn includes(&self, other: &dyn Shape) -> bool {
+        std::ptr::addr_eq(self, other)
+    }
+
+    /// Split the shape into a hierarchy of subgroups, so that rays can
+    /// skip whole subtrees
+    ///
+    /// Only shapes made of other shapes do something here, `threshold`
+    /// is the number of children a `Group` needs before it is divided.
+    fn divide(&mut self, _threshold: usize) {}
+
+    /// Convert a world space `Point` to object space, going through
+    /// the space of every parent `Group` first
+    fn world_to_object(&self, point: Point) -> Point {
+        *self.inverse() * (*self.parent_inverse() * point)
+    }
+
+    /// Convert an object space normal to world space, going through
+    /// the space of every parent `Group` on the way
+    ///
+    /// The normal is transformed with the transpose of the inverse, so
+    /// that it stays perpendicular to the surface even under
+    /// non-uniform scaling.
+    fn normal_to_world(&self, normal: Vector) -> Vector {
+        let parent_normal = self.inverse().transpose() * normal;
+        (self.parent_inverse().transpose() * parent_normal).normalize()
+    }
+
+    /// Intersect a `Ray` with the shape, the `Ray` must be in the space
+    /// of the parent `Group`, which is world space for top level shapes
+    fn intersect(&self, ray: &Ray) -> Intersections<'_> {
+        self.local_intersect(&ray.transform(self.inverse()))
+    }
+
+    /// Compute the normal `Vector` at the world space `point`
+    fn normal_at(&self, point: Point, hit: &Intersection) -> Vector {
+        let local_point = self.world_to_object(point);
+        self.normal_to_world(self.local_normal_at(local_point, hit))
+    }
+}
+
+/// The matrix that takes a world space `Point` to the object space of
+/// `shape`, which is the parent space of its children
+pub(crate) fn children_parent_inverse<S: Shape + ?Sized>(shape: &S) -> Matrix4 {
+    *shape.inverse() * *shape.parent_inverse()
+}
+
+/// Give every child of `shape` the inverse transformations of all the
+/// shapes above it
+pub(crate) fn update_children<S: Shape + ?Sized>(shape: &mut S) {
+    let parent_inverse = children_parent_inverse(shape);
+    for child in shape.children_mut() {
+        child.set_parent_inverse(parent_inverse);
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::transform::{rotation_z, scaling, translation};
+    use crate::tuple::Tuple;
+    use crate::{point, vector};
+    use std::cell::Cell;
+    use std::f64::consts::{FRAC_1_SQRT_2, PI};
+    use std::rc::Rc;
+
+    /// A `Shape` that keeps the last object space `Ray` it was
+    /// intersected with, shared so that it can still be read once the
+    /// shape is inside a `Group`
+    #[derive(Debug, Default)]
+    pub(crate) struct TestShape {
+        data: ShapeData,
+        pub saved_ray: Rc<Cell<Option<Ray>>>,
+    }
+
+    impl Shape for TestShape {
+        fn data(&self) -> &ShapeData {
+            &self.data
+        }
+
+        fn data_mut(&mut self) -> &mut ShapeData {
+            &mut self.data
+        }
+
+        fn local_intersect(&self, ray: &Ray) -> Intersections<'_> {
+            self.saved_ray.set(Some(*ray));
+            Intersections::default()
+        }
+
+        fn local_normal_at(&self, point: Point, _hit: &Intersection) -> Vector {
+            Vector::new(point.x, point.y, point.z)
+        }
+
+        fn bounds(&self) -> Bounds {
+            Bounds::new(point!(-1, -1, -1), point!(1, 1, 1))
+        }
+    }
+
+    #[test]
+    fn default_transformation() {
+        let s = TestShape::default();
+        assert!(*s.transform() == Matrix4::identity());
+    }
+
+    #[test]
+    fn assign_transformation() {
+        let mut s = TestShape::default();
+        s.set_transform(translation(2.0, 3.0, 4.0));
+        assert!(*s.transform() == translation(2.0, 3.0, 4.0));
+    }
+
+    #[test]
+    fn default_material() {
+        let s = TestShape::default();
+        assert_eq!(*s.material(), Material::new());
+    }
+
+    #[test]
+    fn assign_material() {
+        let mut s = TestShape::default();
+        let mut m = Material::new();
+        m.ambient = 1.0;
+        s.set_material(m.clone());
+        assert_eq!(*s.material(), m);
+    }
+
+    #[test]
+    fn shapes_cast_shadows_by_default() {
+        let mut s = TestShape::default();
+        assert!(s.casts_shadow());
+        s.set_casts_shadow(false);
+        assert!(!s.casts_shadow());
+    }
+
+    #[test]
+    fn intersect_scaled_shape() {
+        let r = Ray::new(point!(0, 0, -5), vector!(0, 0, 1));
+        let mut s = TestShape::default();
+        s.set_transform(scaling(2.0, 2.0, 2.0));
+        s.intersect(&r);
+        let saved = s.saved_ray.get().unwrap();
+        assert!(saved.origin == point!(0, 0, -2.5));
+        assert!(saved.direction == vector!(0, 0, 0.5));
+    }
+
+    #[test]
+    fn intersect_translated_shape() {
+        let r = Ray::new(point!(0, 0, -5), vector!(0, 0, 1));
+        let mut s = TestShape::default();
+        s.set_transform(translation(5.0, 0.0, 0.0));
+        s.intersect(&r);
+        let saved = s.saved_ray.get().unwrap();
+        assert!(saved.origin == point!(-5, 0, -5));
+        assert!(saved.direction == vector!(0, 0, 1));
+    }
+
+    #[test]
+    fn normal_on_translated_shape() {
+        let mut s = TestShape::default();
+        s.set_transform(translation(0.0, 1.0, 0.0));
+        let hit = Intersection::new(0.0, &s);
+        let n = s.normal_at(point!(0, 1.0 + FRAC_1_SQRT_2, -FRAC_1_SQRT_2), &hit);
+        assert!(n == vector!(0, FRAC_1_SQRT_2, -FRAC_1_SQRT_2));
+    }
+
+    #[test]
+    fn normal_on_transformed_shape() {
+        let mut s = TestShape::default();
+        s.set_transform(scaling(1.0, 0.5, 1.0) * rotation_z(PI / 5.0));
+        let hit = Intersection::new(0.0, &s);
+        let n = s.normal_at(point!(0, FRAC_1_SQRT_2, -FRAC_1_SQRT_2), &hit);
+        assert!(n.x.abs() < 1e-5);
+        assert!((n.y - 0.97014).abs() < 1e-5);
+        assert!((n.z + 0.24254).abs() < 1e-5);
+    }
+
+    #[test]
+    fn shapes_as_trait_objects() {
+        let r = Ray::new(point!(0, 0, -5), vector!(0, 0, 1));
+        let shapes: Vec<Box<dyn Shape>> =
+            vec![Box::new(Sphere::new()), Box::new(TestShape::default())];
+        let hits: usize = shapes.iter().map(|s| s.intersect(&r).len()).sum();
+        assert_eq!(hits, 2);
+    }
+}
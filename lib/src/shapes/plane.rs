@@ -0,0 +1,118 @@
+use crate::bounds::Bounds;
+use crate::intersection::{Intersection, Intersections};
+use crate::ray::Ray;
+use crate::shapes::{Shape, ShapeData};
+use crate::tuple::utils::EPSILON;
+use crate::tuple::{Point, Tuple, Vector};
+
+/// An infinite plane, the xz plane in its object space
+#[derive(Clone, Debug)]
+pub struct Plane {
+    data: ShapeData,
+}
+
+impl Plane {
+    pub fn new() -> Plane {
+        Plane {
+            data: ShapeData::new(),
+        }
+    }
+}
+
+impl Default for Plane {
+    fn default() -> Self {
+        Plane::new()
+    }
+}
+
+impl Shape for Plane {
+    fn data(&self) -> &ShapeData {
+        &self.data
+    }
+
+    fn data_mut(&mut self) -> &mut ShapeData {
+        &mut self.data
+    }
+
+    /// A `Ray` that is parallel or coplanar to the `Plane` never
+    /// intersects it
+    fn local_intersect(&self, ray: &Ray) -> Intersections<'_> {
+        if ray.direction.y.abs() < EPSILON {
+            return Intersections::default();
+        }
+
+        let t = -ray.origin.y / ray.direction.y;
+        Intersections::new(vec![Intersection::new(t, self)])
+    }
+
+    /// The normal is the same everywhere on the `Plane`
+    fn local_normal_at(&self, _point: Point, _hit: &Intersection) -> Vector {
+        Vector::new(0.0, 1.0, 0.0)
+    }
+
+    /// The `Plane` is infinite in both x and z, and flat in y
+    fn bounds(&self) -> Bounds {
+        Bounds::new(
+            Point::new(f64::NEG_INFINITY, 0.0, f64::NEG_INFINITY),
+            Point::new(f64::INFINITY, 0.0, f64::INFINITY),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{point, vector};
+
+    #[test]
+    fn normal_is_constant() {
+        let p = Plane::new();
+        let hit = Intersection::new(0.0, &p);
+        assert!(p.local_normal_at(point!(0, 0, 0), &hit) == vector!(0, 1, 0));
+        assert!(p.local_normal_at(point!(10, 0, -10), &hit) == vector!(0, 1, 0));
+        assert!(p.local_normal_at(point!(-5, 0, 150), &hit) == vector!(0, 1, 0));
+    }
+
+    #[test]
+    fn intersect_parallel_ray() {
+        let p = Plane::new();
+        let r = Ray::new(point!(0, 10, 0), vector!(0, 0, 1));
+        assert!(p.local_intersect(&r).is_empty());
+    }
+
+    #[test]
+    fn intersect_coplanar_ray() {
+        let p = Plane::new();
+        let r = Ray::new(point!(0, 0, 0), vector!(0, 0, 1));
+        assert!(p.local_intersect(&r).is_empty());
+    }
+
+    #[test]
+    fn intersect_from_above() {
+        let p = Plane::new();
+        let r = Ray::new(point!(0, 1, 0), vector!(0, -1, 0));
+        let xs = p.local_intersect(&r);
+        assert_eq!(xs.len(), 1);
+        assert_eq!(xs[0].t, 1.0);
+        assert!(std::ptr::addr_eq(xs[0].object, &p));
+    }
+
+    #[test]
+    fn intersect_from_below() {
+        let p = Plane::new();
+        let r = Ray::new(point!(0, -1, 0), vector!(0, 1, 0));
+        let xs = p.local_intersect(&r);
+        assert_eq!(xs.len(), 1);
+        assert_eq!(xs[0].t, 1.0);
+    }
+
+    #[test]
+    fn plane_bounds_are_infinite() {
+        let b = Plane::new().bounds();
+        assert!(!b.is_finite());
+        assert_eq!(b.min.x, f64::NEG_INFINITY);
+        assert_eq!(b.min.y, 0.0);
+        assert_eq!(b.max.y, 0.0);
+        assert_eq!(b.max.z, f64::INFINITY);
+    }
+}
@@ -0,0 +1,152 @@
+use crate::bounds::Bounds;
+use crate::intersection::{Intersection, Intersections};
+use crate::ray::Ray;
+use crate::shapes::triangle::intersect_triangle;
+use crate::shapes::{Shape, ShapeData};
+use crate::tuple::{Point, Vector};
+
+/// A triangle with a normal for each vertex, the normal at any point
+/// is interpolated from them so that meshes look smooth
+#[derive(Clone, Debug)]
+pub struct SmoothTriangle {
+    data: ShapeData,
+    p1: Point,
+    p2: Point,
+    p3: Point,
+    n1: Vector,
+    n2: Vector,
+    n3: Vector,
+    e1: Vector,
+    e2: Vector,
+}
+
+impl SmoothTriangle {
+    pub fn new(
+        p1: Point,
+        p2: Point,
+        p3: Point,
+        n1: Vector,
+        n2: Vector,
+        n3: Vector,
+    ) -> SmoothTriangle {
+        SmoothTriangle {
+            data: ShapeData::new(),
+            p1,
+            p2,
+            p3,
+            n1,
+            n2,
+            n3,
+            e1: p2 - p1,
+            e2: p3 - p1,
+        }
+    }
+
+    pub fn p1(&self) -> Point {
+        self.p1
+    }
+
+    pub fn p2(&self) -> Point {
+        self.p2
+    }
+
+    pub fn p3(&self) -> Point {
+        self.p3
+    }
+
+    pub fn n1(&self) -> Vector {
+        self.n1
+    }
+
+    pub fn n2(&self) -> Vector {
+        self.n2
+    }
+
+    pub fn n3(&self) -> Vector {
+        self.n3
+    }
+}
+
+impl Shape for SmoothTriangle {
+    fn data(&self) -> &ShapeData {
+        &self.data
+    }
+
+    fn data_mut(&mut self) -> &mut ShapeData {
+        &mut self.data
+    }
+
+    fn local_intersect(&self, ray: &Ray) -> Intersections<'_> {
+        match intersect_triangle(ray, self.p1, self.e1, self.e2) {
+            Some((t, u, v)) => Intersections::new(vec![Intersection::with_uv(t, self, u, v)]),
+            None => Intersections::default(),
+        }
+    }
+
+    /// Interpolate the vertex normals with the `u` and `v` of the `hit`
+    fn local_normal_at(&self, _point: Point, hit: &Intersection) -> Vector {
+        self.n2 * hit.u + self.n3 * hit.v + self.n1 * (1.0 - hit.u - hit.v)
+    }
+
+    fn bounds(&self) -> Bounds {
+        Bounds::from_points(&[self.p1, self.p2, self.p3])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tuple::Tuple;
+    use crate::{point, vector};
+
+    fn default_smooth_triangle() -> SmoothTriangle {
+        SmoothTriangle::new(
+            point!(0, 1, 0),
+            point!(-1, 0, 0),
+            point!(1, 0, 0),
+            vector!(0, 1, 0),
+            vector!(-1, 0, 0),
+            vector!(1, 0, 0),
+        )
+    }
+
+    #[test]
+    fn construct_smooth_triangle() {
+        let tri = default_smooth_triangle();
+        assert!(tri.p1() == point!(0, 1, 0));
+        assert!(tri.p2() == point!(-1, 0, 0));
+        assert!(tri.p3() == point!(1, 0, 0));
+        assert!(tri.n1() == vector!(0, 1, 0));
+        assert!(tri.n2() == vector!(-1, 0, 0));
+        assert!(tri.n3() == vector!(1, 0, 0));
+    }
+
+    #[test]
+    fn intersection_stores_u_and_v() {
+        let tri = default_smooth_triangle();
+        let r = Ray::new(point!(-0.2, 0.3, -2), vector!(0, 0, 1));
+        let xs = tri.local_intersect(&r);
+        assert!((xs[0].u - 0.45).abs() < 1e-6);
+        assert!((xs[0].v - 0.25).abs() < 1e-6);
+    }
+
+    #[test]
+    fn normal_is_interpolated() {
+        let tri = default_smooth_triangle();
+        let i = Intersection::with_uv(1.0, &tri, 0.45, 0.25);
+        let n = tri.normal_at(point!(0, 0, 0), &i);
+        assert!((n.x + 0.5547).abs() < 1e-4);
+        assert!((n.y - 0.83205).abs() < 1e-5);
+        assert!(n.z.abs() < 1e-5);
+    }
+
+    #[test]
+    fn prepare_normal_on_smooth_triangle() {
+        let tri = default_smooth_triangle();
+        let i = Intersection::with_uv(1.0, &tri, 0.45, 0.25);
+        let r = Ray::new(point!(-0.2, 0.3, -2), vector!(0, 0, 1));
+        let comps = i.prepare_computations(&r, &[i]);
+        assert!((comps.normalv.x + 0.5547).abs() < 1e-4);
+        assert!((comps.normalv.y - 0.83205).abs() < 1e-5);
+    }
+}
@@ -0,0 +1,209 @@
+use crate::bounds::Bounds;
+use crate::intersection::{Intersection, Intersections};
+use crate::ray::Ray;
+use crate::shapes::{Shape, ShapeData};
+use crate::tuple::{Point, Tuple, Vector};
+
+/// A unit sphere centered at the origin of its object space
+#[derive(Clone, Debug)]
+pub struct Sphere {
+    data: ShapeData,
+}
+
+impl Sphere {
+    pub fn new() -> Sphere {
+        Sphere {
+            data: ShapeData::new(),
+        }
+    }
+}
+
+impl Shape for Sphere {
+    fn data(&self) -> &ShapeData {
+        &self.data
+    }
+
+    fn data_mut(&mut self) -> &mut ShapeData {
+        &mut self.data
+    }
+
+    /// Return the distances along `ray` where it enters and leaves
+    /// the `Sphere`, or no `Intersection` at all if it misses
+    ///
+    /// # Examples
+    /// ```
+    /// use ray_tracer::ray::Ray;
+    /// use ray_tracer::shapes::{Shape, Sphere};
+    /// use ray_tracer::tuple::{Point, Tuple, Vector};
+    /// use ray_tracer::{point, vector};
+    ///
+    /// let r = Ray::new(point!(0, 0, -5), vector!(0, 0, 1));
+    /// let s = Sphere::new();
+    /// let xs = s.intersect(&r);
+    /// assert_eq!(xs[0].t, 4.0);
+    /// assert_eq!(xs[1].t, 6.0);
+    /// ```
+    fn local_intersect(&self, ray: &Ray) -> Intersections<'_> {
+        let sphere_to_ray = ray.origin - Point::origin();
+
+        let a = ray.direction.dot(&ray.direction);
+        let b = 2.0 * ray.direction.dot(&sphere_to_ray);
+        let c = sphere_to_ray.dot(&sphere_to_ray) - 1.0;
+        let discriminant = b * b - 4.0 * a * c;
+
+        if discriminant < 0.0 {
+            return Intersections::default();
+        }
+
+        let sqrt_disc = discriminant.sqrt();
+        let t1 = (-b - sqrt_disc) / (2.0 * a);
+        let t2 = (-b + sqrt_disc) / (2.0 * a);
+
+        Intersections::new(vec![
+            Intersection::new(t1, self),
+            Intersection::new(t2, self),
+        ])
+    }
+
+    fn local_normal_at(&self, point: Point, _hit: &Intersection) -> Vector {
+        point - Point::origin()
+    }
+
+    fn bounds(&self) -> Bounds {
+        Bounds::new(Point::new(-1.0, -1.0, -1.0), Point::new(1.0, 1.0, 1.0))
+    }
+}
+
+impl Default for Sphere {
+    fn default() -> Self {
+        Sphere::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transform::{rotation_z, scaling, translation};
+    use crate::{point, vector};
+    use std::f64::consts::{FRAC_1_SQRT_2, PI};
+
+    #[test]
+    fn ray_intersects_at_two_points() {
+        let r = Ray::new(point!(0, 0, -5), vector!(0, 0, 1));
+        let s = Sphere::new();
+        let xs = s.intersect(&r);
+        assert_eq!(xs.len(), 2);
+        assert_eq!(xs[0].t, 4.0);
+        assert_eq!(xs[1].t, 6.0);
+    }
+
+    #[test]
+    fn ray_intersects_at_tangent() {
+        let r = Ray::new(point!(0, 1, -5), vector!(0, 0, 1));
+        let s = Sphere::new();
+        let xs = s.intersect(&r);
+        assert_eq!(xs.len(), 2);
+        assert_eq!(xs[0].t, 5.0);
+        assert_eq!(xs[1].t, 5.0);
+    }
+
+    #[test]
+    fn ray_misses_sphere() {
+        let r = Ray::new(point!(0, 2, -5), vector!(0, 0, 1));
+        let s = Sphere::new();
+        assert!(s.intersect(&r).is_empty());
+    }
+
+    #[test]
+    fn ray_originates_inside_sphere() {
+        let r = Ray::new(point!(0, 0, 0), vector!(0, 0, 1));
+        let s = Sphere::new();
+        let xs = s.intersect(&r);
+        assert_eq!(xs[0].t, -1.0);
+        assert_eq!(xs[1].t, 1.0);
+    }
+
+    #[test]
+    fn sphere_behind_ray() {
+        let r = Ray::new(point!(0, 0, 5), vector!(0, 0, 1));
+        let s = Sphere::new();
+        let xs = s.intersect(&r);
+        assert_eq!(xs[0].t, -6.0);
+        assert_eq!(xs[1].t, -4.0);
+    }
+
+    #[test]
+    fn intersect_sets_object() {
+        let r = Ray::new(point!(0, 0, -5), vector!(0, 0, 1));
+        let s = Sphere::new();
+        let xs = s.intersect(&r);
+        assert!(std::ptr::addr_eq(xs[0].object, &s));
+        assert!(std::ptr::addr_eq(xs[1].object, &s));
+    }
+
+    #[test]
+    fn intersect_scaled_sphere() {
+        let r = Ray::new(point!(0, 0, -5), vector!(0, 0, 1));
+        let mut s = Sphere::new();
+        s.set_transform(scaling(2.0, 2.0, 2.0));
+        let xs = s.intersect(&r);
+        assert_eq!(xs.len(), 2);
+        assert_eq!(xs[0].t, 3.0);
+        assert_eq!(xs[1].t, 7.0);
+    }
+
+    #[test]
+    fn intersect_translated_sphere() {
+        let r = Ray::new(point!(0, 0, -5), vector!(0, 0, 1));
+        let mut s = Sphere::new();
+        s.set_transform(translation(5.0, 0.0, 0.0));
+        assert!(s.intersect(&r).is_empty());
+    }
+
+    #[test]
+    fn normals_on_axes() {
+        let s = Sphere::new();
+        let hit = Intersection::new(0.0, &s);
+        assert!(s.normal_at(point!(1, 0, 0), &hit) == vector!(1, 0, 0));
+        assert!(s.normal_at(point!(0, 1, 0), &hit) == vector!(0, 1, 0));
+        assert!(s.normal_at(point!(0, 0, 1), &hit) == vector!(0, 0, 1));
+    }
+
+    #[test]
+    fn normal_is_normalized() {
+        let s = Sphere::new();
+        let a = 3.0_f64.sqrt() / 3.0;
+        let hit = Intersection::new(0.0, &s);
+        let n = s.normal_at(point!(a, a, a), &hit);
+        assert!(n == vector!(a, a, a));
+        assert!(n == n.normalize());
+    }
+
+    #[test]
+    fn normal_on_translated_sphere() {
+        let mut s = Sphere::new();
+        s.set_transform(translation(0.0, 1.0, 0.0));
+        let hit = Intersection::new(0.0, &s);
+        let n = s.normal_at(point!(0, 1.0 + FRAC_1_SQRT_2, -FRAC_1_SQRT_2), &hit);
+        assert!(n == vector!(0, FRAC_1_SQRT_2, -FRAC_1_SQRT_2));
+    }
+
+    #[test]
+    fn normal_on_transformed_sphere() {
+        let mut s = Sphere::new();
+        s.set_transform(scaling(1.0, 0.5, 1.0) * rotation_z(PI / 5.0));
+        let hit = Intersection::new(0.0, &s);
+        let n = s.normal_at(point!(0, FRAC_1_SQRT_2, -FRAC_1_SQRT_2), &hit);
+        assert!((n.x - 0.0).abs() < 1e-5);
+        assert!((n.y - 0.97014).abs() < 1e-5);
+        assert!((n.z + 0.24254).abs() < 1e-5);
+    }
+
+    #[test]
+    fn sphere_bounds() {
+        let s = Sphere::new();
+        let b = s.bounds();
+        assert!(b.min == point!(-1, -1, -1));
+        assert!(b.max == point!(1, 1, 1));
+    }
+}
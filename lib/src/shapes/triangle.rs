@@ -0,0 +1,196 @@
+use crate::bounds::Bounds;
+use crate::intersection::{Intersection, Intersections};
+use crate::ray::Ray;
+use crate::shapes::{Shape, ShapeData};
+use crate::tuple::utils::EPSILON;
+use crate::tuple::{Point, Vector};
+
+/// A flat triangle given by its three vertices
+///
+/// The edges and the normal are precomputed when it is created, which
+/// is why the vertices can't be changed afterwards.
+#[derive(Clone, Debug)]
+pub struct Triangle {
+    data: ShapeData,
+    p1: Point,
+    p2: Point,
+    p3: Point,
+    e1: Vector,
+    e2: Vector,
+    normal: Vector,
+}
+
+impl Triangle {
+    pub fn new(p1: Point, p2: Point, p3: Point) -> Triangle {
+        let e1 = p2 - p1;
+        let e2 = p3 - p1;
+
+        Triangle {
+            data: ShapeData::new(),
+            p1,
+            p2,
+            p3,
+            e1,
+            e2,
+            normal: e2.cross(&e1).normalize(),
+        }
+    }
+
+    pub fn p1(&self) -> Point {
+        self.p1
+    }
+
+    pub fn p2(&self) -> Point {
+        self.p2
+    }
+
+    pub fn p3(&self) -> Point {
+        self.p3
+    }
+
+    pub fn e1(&self) -> Vector {
+        self.e1
+    }
+
+    pub fn e2(&self) -> Vector {
+        self.e2
+    }
+
+    pub fn normal(&self) -> Vector {
+        self.normal
+    }
+}
+
+/// Intersect `ray` with the triangle at `p1` with edges `e1` and `e2`,
+/// using the Möller–Trumbore algorithm
+///
+/// Returns the distance `t` and the barycentric coordinates `u` and `v`
+/// of the hit, if there is one.
+pub(crate) fn intersect_triangle(
+    ray: &Ray,
+    p1: Point,
+    e1: Vector,
+    e2: Vector,
+) -> Option<(f64, f64, f64)> {
+    let dir_cross_e2 = ray.direction.cross(&e2);
+    let determinant = e1.dot(&dir_cross_e2);
+    // The ray is parallel to the triangle
+    if determinant.abs() < EPSILON {
+        return None;
+    }
+
+    let f = 1.0 / determinant;
+    let p1_to_origin = ray.origin - p1;
+    let u = f * p1_to_origin.dot(&dir_cross_e2);
+    if !(0.0..=1.0).contains(&u) {
+        return None;
+    }
+
+    let origin_cross_e1 = p1_to_origin.cross(&e1);
+    let v = f * ray.direction.dot(&origin_cross_e1);
+    if v < 0.0 || u + v > 1.0 {
+        return None;
+    }
+
+    Some((f * e2.dot(&origin_cross_e1), u, v))
+}
+
+impl Shape for Triangle {
+    fn data(&self) -> &ShapeData {
+        &self.data
+    }
+
+    fn data_mut(&mut self) -> &mut ShapeData {
+        &mut self.data
+    }
+
+    fn local_intersect(&self, ray: &Ray) -> Intersections<'_> {
+        match intersect_triangle(ray, self.p1, self.e1, self.e2) {
+            Some((t, u, v)) => Intersections::new(vec![Intersection::with_uv(t, self, u, v)]),
+            None => Intersections::default(),
+        }
+    }
+
+    /// The normal is the same everywhere on the `Triangle`
+    fn local_normal_at(&self, _point: Point, _hit: &Intersection) -> Vector {
+        self.normal
+    }
+
+    fn bounds(&self) -> Bounds {
+        Bounds::from_points(&[self.p1, self.p2, self.p3])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tuple::Tuple;
+    use crate::{point, vector};
+
+    fn default_triangle() -> Triangle {
+        Triangle::new(point!(0, 1, 0), point!(-1, 0, 0), point!(1, 0, 0))
+    }
+
+    #[test]
+    fn construct_triangle() {
+        let t = default_triangle();
+        assert!(t.p1() == point!(0, 1, 0));
+        assert!(t.p2() == point!(-1, 0, 0));
+        assert!(t.p3() == point!(1, 0, 0));
+        assert!(t.e1() == vector!(-1, -1, 0));
+        assert!(t.e2() == vector!(1, -1, 0));
+        assert!(t.normal() == vector!(0, 0, -1));
+    }
+
+    #[test]
+    fn normal_is_constant() {
+        let t = default_triangle();
+        let hit = Intersection::new(0.0, &t);
+        assert!(t.local_normal_at(point!(0, 0.5, 0), &hit) == t.normal());
+        assert!(t.local_normal_at(point!(-0.5, 0.75, 0), &hit) == t.normal());
+        assert!(t.local_normal_at(point!(0.5, 0.25, 0), &hit) == t.normal());
+    }
+
+    #[test]
+    fn ray_parallel_to_triangle() {
+        let t = default_triangle();
+        let r = Ray::new(point!(0, -1, -2), vector!(0, 1, 0));
+        assert!(t.local_intersect(&r).is_empty());
+    }
+
+    #[test]
+    fn ray_misses_edges() {
+        let t = default_triangle();
+        // p1-p3 edge, p1-p2 edge and p2-p3 edge
+        for origin in [point!(1, 1, -2), point!(-1, 1, -2), point!(0, -1, -2)] {
+            let r = Ray::new(origin, vector!(0, 0, 1));
+            assert!(t.local_intersect(&r).is_empty());
+        }
+    }
+
+    #[test]
+    fn ray_strikes_triangle() {
+        let t = default_triangle();
+        let r = Ray::new(point!(0, 0.5, -2), vector!(0, 0, 1));
+        let xs = t.local_intersect(&r);
+        assert_eq!(xs.len(), 1);
+        assert_eq!(xs[0].t, 2.0);
+    }
+
+    #[test]
+    fn intersection_stores_u_and_v() {
+        let t = default_triangle();
+        let r = Ray::new(point!(-0.2, 0.3, -2), vector!(0, 0, 1));
+        let xs = t.local_intersect(&r);
+        assert!((xs[0].u - 0.45).abs() < 1e-6);
+        assert!((xs[0].v - 0.25).abs() < 1e-6);
+    }
+
+    #[test]
+    fn triangle_bounds() {
+        let t = Triangle::new(point!(-3, 7, 2), point!(6, 2, -4), point!(2, -1, -1));
+        let b = t.bounds();
+        assert!(b.min == point!(-3, -1, -4));
+        assert!(b.max == point!(6, 7, 2));
+    }
+}
@@ -0,0 +1,297 @@
+//! Transformation matrices and a fluent API to chain them
+use crate::tuple::{Matrix4, Point, Vector};
+
+/// A `Transform` is just a `Matrix4`, the alias is there to make
+/// chained transformations read better.
+///
+/// # Examples
+/// ```
+/// use ray_tracer::transform::Transform;
+/// use ray_tracer::tuple::{Point, Tuple};
+/// use ray_tracer::point;
+/// use std::f64::consts::PI;
+///
+/// let t = Transform::identity()
+///     .rotate_x(PI / 2.0)
+///     .scale(5.0, 5.0, 5.0)
+///     .translate(10.0, 5.0, 7.0);
+/// assert!(t * point!(1, 0, 1) == point!(15, 0, 7));
+/// ```
+pub type Transform = Matrix4;
+
+/// Create a translation `Matrix4`
+///
+/// Translations only move `Point`s, `Vector`s are left untouched.
+pub fn translation(x: f64, y: f64, z: f64) -> Matrix4 {
+    Matrix4::new([
+        [1.0, 0.0, 0.0, x],
+        [0.0, 1.0, 0.0, y],
+        [0.0, 0.0, 1.0, z],
+        [0.0, 0.0, 0.0, 1.0],
+    ])
+}
+
+/// Create a scaling `Matrix4`
+pub fn scaling(x: f64, y: f64, z: f64) -> Matrix4 {
+    Matrix4::new([
+        [x, 0.0, 0.0, 0.0],
+        [0.0, y, 0.0, 0.0],
+        [0.0, 0.0, z, 0.0],
+        [0.0, 0.0, 0.0, 1.0],
+    ])
+}
+
+/// Create a rotation `Matrix4` around the x axis, `radians` is
+/// measured following the left-hand rule
+pub fn rotation_x(radians: f64) -> Matrix4 {
+    let (sin, cos) = radians.sin_cos();
+    Matrix4::new([
+        [1.0, 0.0, 0.0, 0.0],
+        [0.0, cos, -sin, 0.0],
+        [0.0, sin, cos, 0.0],
+        [0.0, 0.0, 0.0, 1.0],
+    ])
+}
+
+/// Create a rotation `Matrix4` around the y axis, `radians` is
+/// measured following the left-hand rule
+pub fn rotation_y(radians: f64) -> Matrix4 {
+    let (sin, cos) = radians.sin_cos();
+    Matrix4::new([
+        [cos, 0.0, sin, 0.0],
+        [0.0, 1.0, 0.0, 0.0],
+        [-sin, 0.0, cos, 0.0],
+        [0.0, 0.0, 0.0, 1.0],
+    ])
+}
+
+/// Create a rotation `Matrix4` around the z axis, `radians` is
+/// measured following the left-hand rule
+pub fn rotation_z(radians: f64) -> Matrix4 {
+    let (sin, cos) = radians.sin_cos();
+    Matrix4::new([
+        [cos, -sin, 0.0, 0.0],
+        [sin, cos, 0.0, 0.0],
+        [0.0, 0.0, 1.0, 0.0],
+        [0.0, 0.0, 0.0, 1.0],
+    ])
+}
+
+/// Create a shearing `Matrix4`
+///
+/// Each argument moves a component in proportion to another one,
+/// e.g. `xy` moves `x` in proportion to `y`.
+pub fn shearing(xy: f64, xz: f64, yx: f64, yz: f64, zx: f64, zy: f64) -> Matrix4 {
+    Matrix4::new([
+        [1.0, xy, xz, 0.0],
+        [yx, 1.0, yz, 0.0],
+        [zx, zy, 1.0, 0.0],
+        [0.0, 0.0, 0.0, 1.0],
+    ])
+}
+
+/// Create the transformation that orients the world relative to an
+/// eye placed at `from`, looking at `to`, with `up` roughly upwards
+///
+/// # Examples
+/// ```
+/// use ray_tracer::transform::view_transform;
+/// use ray_tracer::tuple::{Matrix4, Point, Tuple, Vector};
+/// use ray_tracer::{point, vector};
+///
+/// let t = view_transform(point!(0, 0, 0), point!(0, 0, -1), vector!(0, 1, 0));
+/// assert!(t == Matrix4::identity());
+/// ```
+pub fn view_transform(from: Point, to: Point, up: Vector) -> Matrix4 {
+    let forward = (to - from).normalize();
+    let left = forward.cross(&up.normalize());
+    // Recompute `up` so that it is exactly perpendicular
+    let true_up = left.cross(&forward);
+
+    let orientation = Matrix4::new([
+        [left.x, left.y, left.z, 0.0],
+        [true_up.x, true_up.y, true_up.z, 0.0],
+        [-forward.x, -forward.y, -forward.z, 0.0],
+        [0.0, 0.0, 0.0, 1.0],
+    ]);
+
+    orientation * translation(-from.x, -from.y, -from.z)
+}
+
+// * Fluent API, every method applies its transformation *after* the
+// * ones already in the `Matrix4`
+impl Matrix4 {
+    pub fn translate(self, x: f64, y: f64, z: f64) -> Matrix4 {
+        translation(x, y, z) * self
+    }
+
+    pub fn scale(self, x: f64, y: f64, z: f64) -> Matrix4 {
+        scaling(x, y, z) * self
+    }
+
+    pub fn rotate_x(self, radians: f64) -> Matrix4 {
+        rotation_x(radians) * self
+    }
+
+    pub fn rotate_y(self, radians: f64) -> Matrix4 {
+        rotation_y(radians) * self
+    }
+
+    pub fn rotate_z(self, radians: f64) -> Matrix4 {
+        rotation_z(radians) * self
+    }
+
+    pub fn shear(self, xy: f64, xz: f64, yx: f64, yz: f64, zx: f64, zy: f64) -> Matrix4 {
+        shearing(xy, xz, yx, yz, zx, zy) * self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tuple::Tuple;
+    use crate::{point, vector};
+    use std::f64::consts::PI;
+
+    #[test]
+    fn translate_point() {
+        let t = translation(5.0, -3.0, 2.0);
+        let p = point!(-3, 4, 5);
+        assert!(t * p == point!(2, 1, 7));
+
+        let inv = t.inverse().unwrap();
+        assert!(inv * p == point!(-8, 7, 3));
+    }
+
+    #[test]
+    fn translation_does_not_affect_vectors() {
+        let t = translation(5.0, -3.0, 2.0);
+        let v = vector!(-3, 4, 5);
+        assert!(t * v == v);
+    }
+
+    #[test]
+    fn scale_point_and_vector() {
+        let t = scaling(2.0, 3.0, 4.0);
+        assert!(t * point!(-4, 6, 8) == point!(-8, 18, 32));
+        assert!(t * vector!(-4, 6, 8) == vector!(-8, 18, 32));
+
+        let inv = t.inverse().unwrap();
+        assert!(inv * vector!(-4, 6, 8) == vector!(-2, 2, 2));
+    }
+
+    #[test]
+    fn reflection_is_negative_scaling() {
+        let t = scaling(-1.0, 1.0, 1.0);
+        assert!(t * point!(2, 3, 4) == point!(-2, 3, 4));
+    }
+
+    #[test]
+    fn rotate_around_x() {
+        let p = point!(0, 1, 0);
+        let half_quarter = rotation_x(PI / 4.0);
+        let full_quarter = rotation_x(PI / 2.0);
+        let half = 2.0_f64.sqrt() / 2.0;
+        assert!(half_quarter * p == point!(0, half, half));
+        assert!(full_quarter * p == point!(0, 0, 1));
+        // The inverse rotates in the opposite direction
+        let inv = half_quarter.inverse().unwrap();
+        assert!(inv * p == point!(0, half, -half));
+    }
+
+    #[test]
+    fn rotate_around_y() {
+        let p = point!(0, 0, 1);
+        let half_quarter = rotation_y(PI / 4.0);
+        let full_quarter = rotation_y(PI / 2.0);
+        let half = 2.0_f64.sqrt() / 2.0;
+        assert!(half_quarter * p == point!(half, 0, half));
+        assert!(full_quarter * p == point!(1, 0, 0));
+    }
+
+    #[test]
+    fn rotate_around_z() {
+        let p = point!(0, 1, 0);
+        let half_quarter = rotation_z(PI / 4.0);
+        let full_quarter = rotation_z(PI / 2.0);
+        let half = 2.0_f64.sqrt() / 2.0;
+        assert!(half_quarter * p == point!(-half, half, 0));
+        assert!(full_quarter * p == point!(-1, 0, 0));
+    }
+
+    #[test]
+    fn shear_point() {
+        let p = point!(2, 3, 4);
+        assert!(shearing(1.0, 0.0, 0.0, 0.0, 0.0, 0.0) * p == point!(5, 3, 4));
+        assert!(shearing(0.0, 1.0, 0.0, 0.0, 0.0, 0.0) * p == point!(6, 3, 4));
+        assert!(shearing(0.0, 0.0, 1.0, 0.0, 0.0, 0.0) * p == point!(2, 5, 4));
+        assert!(shearing(0.0, 0.0, 0.0, 1.0, 0.0, 0.0) * p == point!(2, 7, 4));
+        assert!(shearing(0.0, 0.0, 0.0, 0.0, 1.0, 0.0) * p == point!(2, 3, 6));
+        assert!(shearing(0.0, 0.0, 0.0, 0.0, 0.0, 1.0) * p == point!(2, 3, 7));
+    }
+
+    #[test]
+    fn transformations_in_sequence() {
+        let p = point!(1, 0, 1);
+        let a = rotation_x(PI / 2.0);
+        let b = scaling(5.0, 5.0, 5.0);
+        let c = translation(10.0, 5.0, 7.0);
+
+        let p2 = a * p;
+        assert!(p2 == point!(1, -1, 0));
+        let p3 = b * p2;
+        assert!(p3 == point!(5, -5, 0));
+        let p4 = c * p3;
+        assert!(p4 == point!(15, 0, 7));
+
+        // Chained transformations are applied in reverse order
+        assert!(c * b * a * p == point!(15, 0, 7));
+    }
+
+    #[test]
+    fn fluent_transformations() {
+        let t = Transform::identity()
+            .rotate_x(PI / 2.0)
+            .scale(5.0, 5.0, 5.0)
+            .translate(10.0, 5.0, 7.0);
+        let expected = translation(10.0, 5.0, 7.0) * scaling(5.0, 5.0, 5.0) * rotation_x(PI / 2.0);
+        assert!(t == expected);
+        assert!(t * point!(1, 0, 1) == point!(15, 0, 7));
+        // Only the rotation and scaling affect a `Vector`
+        assert!(t * vector!(1, 0, 1) == vector!(5, -5, 0));
+    }
+
+    #[test]
+    fn view_transform_default_orientation() {
+        let t = view_transform(point!(0, 0, 0), point!(0, 0, -1), vector!(0, 1, 0));
+        assert!(t == Matrix4::identity());
+    }
+
+    #[test]
+    fn view_transform_looking_positive_z() {
+        let t = view_transform(point!(0, 0, 0), point!(0, 0, 1), vector!(0, 1, 0));
+        assert!(t == scaling(-1.0, 1.0, -1.0));
+    }
+
+    #[test]
+    fn view_transform_moves_the_world() {
+        let t = view_transform(point!(0, 0, 8), point!(0, 0, 0), vector!(0, 1, 0));
+        assert!(t == translation(0.0, 0.0, -8.0));
+    }
+
+    #[test]
+    fn arbitrary_view_transform() {
+        let t = view_transform(point!(1, 3, 2), point!(4, -2, 8), vector!(1, 1, 0));
+        let expected = [
+            [-0.50709, 0.50709, 0.67612, -2.36643],
+            [0.76772, 0.60609, 0.12122, -2.82843],
+            [-0.35857, 0.59761, -0.71714, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ];
+        for (row, values) in expected.iter().enumerate() {
+            for (col, value) in values.iter().enumerate() {
+                assert!((t[(row, col)] - value).abs() < 1e-5);
+            }
+        }
+    }
+}
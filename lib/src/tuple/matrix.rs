@@ -0,0 +1,523 @@
+//! Square matrices used to transform `Point`s and `Vector`s
+use crate::tuple::utils::float_eq;
+use crate::tuple::{Point, Tuple, Vector};
+use std::ops::{Index, IndexMut, Mul};
+
+/// A 2x2 matrix, only used to compute the determinant of a `Matrix3`
+#[derive(Copy, Clone, Debug)]
+pub struct Matrix2 {
+    pub data: [[f64; 2]; 2],
+}
+
+/// A 3x3 matrix, only used to compute the determinant of a `Matrix4`
+#[derive(Copy, Clone, Debug)]
+pub struct Matrix3 {
+    pub data: [[f64; 3]; 3],
+}
+
+/// A 4x4 matrix, used for every transformation in the ray tracer
+#[derive(Copy, Clone, Debug)]
+pub struct Matrix4 {
+    pub data: [[f64; 4]; 4],
+}
+
+// * Methods for `Matrix2`
+impl Matrix2 {
+    pub fn new(data: [[f64; 2]; 2]) -> Matrix2 {
+        Matrix2 { data }
+    }
+
+    /// Compute the determinant of a `Matrix2`
+    ///
+    /// # Examples
+    /// ```
+    /// use ray_tracer::tuple::matrix::Matrix2;
+    ///
+    /// let m = Matrix2::new([[1.0, 5.0], [-3.0, 2.0]]);
+    /// assert_eq!(m.determinant(), 17.0);
+    /// ```
+    pub fn determinant(&self) -> f64 {
+        self.data[0][0] * self.data[1][1] - self.data[0][1] * self.data[1][0]
+    }
+}
+
+// * Methods for `Matrix3`
+impl Matrix3 {
+    pub fn new(data: [[f64; 3]; 3]) -> Matrix3 {
+        Matrix3 { data }
+    }
+
+    /// Return a copy of the `Matrix3` with the `row` and `col` removed
+    pub fn submatrix(&self, row: usize, col: usize) -> Matrix2 {
+        let mut data = [[0.0; 2]; 2];
+
+        for (new_row, old_row) in (0..3).filter(|&r| r != row).enumerate() {
+            for (new_col, old_col) in (0..3).filter(|&c| c != col).enumerate() {
+                data[new_row][new_col] = self.data[old_row][old_col];
+            }
+        }
+
+        Matrix2::new(data)
+    }
+
+    /// The determinant of the submatrix at (`row`, `col`)
+    pub fn minor(&self, row: usize, col: usize) -> f64 {
+        self.submatrix(row, col).determinant()
+    }
+
+    /// The minor at (`row`, `col`), negated when `row + col` is odd
+    pub fn cofactor(&self, row: usize, col: usize) -> f64 {
+        let minor = self.minor(row, col);
+
+        if (row + col).is_multiple_of(2) {
+            minor
+        } else {
+            -minor
+        }
+    }
+
+    /// Compute the determinant of a `Matrix3` by expanding the first row
+    pub fn determinant(&self) -> f64 {
+        (0..3)
+            .map(|col| self.data[0][col] * self.cofactor(0, col))
+            .sum()
+    }
+}
+
+// * Methods for `Matrix4`
+impl Matrix4 {
+    pub fn new(data: [[f64; 4]; 4]) -> Matrix4 {
+        Matrix4 { data }
+    }
+
+    /// Create the identity `Matrix4`
+    ///
+    /// # Examples
+    /// ```
+    /// use ray_tracer::tuple::matrix::Matrix4;
+    /// use ray_tracer::tuple::{Point, Tuple};
+    /// use ray_tracer::point;
+    ///
+    /// let p = point!(1, 2, 3);
+    /// assert!(Matrix4::identity() * p == p);
+    /// ```
+    pub fn identity() -> Matrix4 {
+        Matrix4::new([
+            [1.0, 0.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+
+    /// Swap the rows and columns of the `Matrix4`
+    pub fn transpose(&self) -> Matrix4 {
+        let mut data = [[0.0; 4]; 4];
+
+        for (row, values) in data.iter_mut().enumerate() {
+            for (col, value) in values.iter_mut().enumerate() {
+                *value = self.data[col][row];
+            }
+        }
+
+        Matrix4::new(data)
+    }
+
+    /// Return a copy of the `Matrix4` with the `row` and `col` removed
+    pub fn submatrix(&self, row: usize, col: usize) -> Matrix3 {
+        let mut data = [[0.0; 3]; 3];
+
+        for (new_row, old_row) in (0..4).filter(|&r| r != row).enumerate() {
+            for (new_col, old_col) in (0..4).filter(|&c| c != col).enumerate() {
+                data[new_row][new_col] = self.data[old_row][old_col];
+            }
+        }
+
+        Matrix3::new(data)
+    }
+
+    /// The determinant of the submatrix at (`row`, `col`)
+    pub fn minor(&self, row: usize, col: usize) -> f64 {
+        self.submatrix(row, col).determinant()
+    }
+
+    /// The minor at (`row`, `col`), negated when `row + col` is odd
+    pub fn cofactor(&self, row: usize, col: usize) -> f64 {
+        let minor = self.minor(row, col);
+
+        if (row + col).is_multiple_of(2) {
+            minor
+        } else {
+            -minor
+        }
+    }
+
+    /// Compute the determinant of a `Matrix4` by expanding the first row
+    pub fn determinant(&self) -> f64 {
+        (0..4)
+            .map(|col| self.data[0][col] * self.cofactor(0, col))
+            .sum()
+    }
+
+    /// A `Matrix4` can only be inverted if its determinant is not zero
+    ///
+    /// Determinants within `EPSILON` of zero count as zero, the inverse
+    /// of such a nearly singular matrix would be dominated by rounding
+    /// errors.
+    pub fn is_invertible(&self) -> bool {
+        !float_eq(self.determinant(), 0.0)
+    }
+
+    /// Compute the inverse of the `Matrix4`
+    ///
+    /// Returns `None` if the matrix is not invertible, see
+    /// `Matrix4::is_invertible`.
+    ///
+    /// # Examples
+    /// ```
+    /// use ray_tracer::tuple::matrix::Matrix4;
+    ///
+    /// let m = Matrix4::new([
+    ///     [3.0, -9.0, 7.0, 3.0],
+    ///     [3.0, -8.0, 2.0, -9.0],
+    ///     [-4.0, 4.0, 4.0, 1.0],
+    ///     [-6.0, 5.0, -1.0, 1.0],
+    /// ]);
+    /// let inverse = m.inverse().unwrap();
+    /// assert!(m * inverse == Matrix4::identity());
+    /// ```
+    pub fn inverse(&self) -> Option<Matrix4> {
+        let determinant = self.determinant();
+        if float_eq(determinant, 0.0) {
+            return None;
+        }
+
+        let mut data = [[0.0; 4]; 4];
+        for (row, values) in data.iter_mut().enumerate() {
+            for (col, value) in values.iter_mut().enumerate() {
+                // Transposing is done by swapping `row` and `col` here
+                *value = self.cofactor(col, row) / determinant;
+            }
+        }
+
+        Some(Matrix4::new(data))
+    }
+}
+
+// * Trait implementations
+impl Index<(usize, usize)> for Matrix4 {
+    type Output = f64;
+
+    fn index(&self, (row, col): (usize, usize)) -> &f64 {
+        &self.data[row][col]
+    }
+}
+
+impl IndexMut<(usize, usize)> for Matrix4 {
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut f64 {
+        &mut self.data[row][col]
+    }
+}
+
+impl PartialEq for Matrix2 {
+    fn eq(&self, other: &Self) -> bool {
+        self.data
+            .iter()
+            .flatten()
+            .zip(other.data.iter().flatten())
+            .all(|(&a, &b)| float_eq(a, b))
+    }
+}
+
+impl PartialEq for Matrix3 {
+    fn eq(&self, other: &Self) -> bool {
+        self.data
+            .iter()
+            .flatten()
+            .zip(other.data.iter().flatten())
+            .all(|(&a, &b)| float_eq(a, b))
+    }
+}
+
+impl PartialEq for Matrix4 {
+    /// Two `Matrix4`s are equal if all of their elements are equal
+    /// up to `EPSILON`
+    fn eq(&self, other: &Self) -> bool {
+        self.data
+            .iter()
+            .flatten()
+            .zip(other.data.iter().flatten())
+            .all(|(&a, &b)| float_eq(a, b))
+    }
+}
+
+/// Multiplication of two `Matrix4`s
+impl Mul for Matrix4 {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        let mut data = [[0.0; 4]; 4];
+
+        for (row, values) in data.iter_mut().enumerate() {
+            for (col, value) in values.iter_mut().enumerate() {
+                *value = (0..4).map(|k| self.data[row][k] * rhs.data[k][col]).sum();
+            }
+        }
+
+        Matrix4::new(data)
+    }
+}
+
+/// Multiplication of a `Matrix4` and a `Point`
+///
+/// A `Point` has a `w` of 1.0, so translations move it.
+impl Mul<Point> for Matrix4 {
+    type Output = Point;
+
+    fn mul(self, rhs: Point) -> Point {
+        let m = &self.data;
+        Point::new(
+            m[0][0] * rhs.x + m[0][1] * rhs.y + m[0][2] * rhs.z + m[0][3],
+            m[1][0] * rhs.x + m[1][1] * rhs.y + m[1][2] * rhs.z + m[1][3],
+            m[2][0] * rhs.x + m[2][1] * rhs.y + m[2][2] * rhs.z + m[2][3],
+        )
+    }
+}
+
+/// Multiplication of a `Matrix4` and a `Vector`
+///
+/// A `Vector` has a `w` of 0.0, so translations leave it untouched.
+impl Mul<Vector> for Matrix4 {
+    type Output = Vector;
+
+    fn mul(self, rhs: Vector) -> Vector {
+        let m = &self.data;
+        Vector::new(
+            m[0][0] * rhs.x + m[0][1] * rhs.y + m[0][2] * rhs.z,
+            m[1][0] * rhs.x + m[1][1] * rhs.y + m[1][2] * rhs.z,
+            m[2][0] * rhs.x + m[2][1] * rhs.y + m[2][2] * rhs.z,
+        )
+    }
+}
+
+// * Unit tests for the matrices
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{point, vector};
+
+    #[test]
+    fn construct_matrix4() {
+        let m = Matrix4::new([
+            [1.0, 2.0, 3.0, 4.0],
+            [5.5, 6.5, 7.5, 8.5],
+            [9.0, 10.0, 11.0, 12.0],
+            [13.5, 14.5, 15.5, 16.5],
+        ]);
+        assert_eq!(m[(0, 0)], 1.0);
+        assert_eq!(m[(0, 3)], 4.0);
+        assert_eq!(m[(1, 0)], 5.5);
+        assert_eq!(m[(1, 2)], 7.5);
+        assert_eq!(m[(2, 2)], 11.0);
+        assert_eq!(m[(3, 0)], 13.5);
+        assert_eq!(m[(3, 2)], 15.5);
+    }
+
+    #[test]
+    fn matrix_equality() {
+        let a = Matrix4::new([
+            [1.0, 2.0, 3.0, 4.0],
+            [5.0, 6.0, 7.0, 8.0],
+            [9.0, 8.0, 7.0, 6.0],
+            [5.0, 4.0, 3.0, 2.0],
+        ]);
+        let mut b = a;
+        assert!(a == b);
+
+        b[(3, 3)] = 2.1;
+        assert!(a != b);
+    }
+
+    #[test]
+    fn multiply_two_matrices() {
+        let a = Matrix4::new([
+            [1.0, 2.0, 3.0, 4.0],
+            [5.0, 6.0, 7.0, 8.0],
+            [9.0, 8.0, 7.0, 6.0],
+            [5.0, 4.0, 3.0, 2.0],
+        ]);
+        let b = Matrix4::new([
+            [-2.0, 1.0, 2.0, 3.0],
+            [3.0, 2.0, 1.0, -1.0],
+            [4.0, 3.0, 6.0, 5.0],
+            [1.0, 2.0, 7.0, 8.0],
+        ]);
+        let result = Matrix4::new([
+            [20.0, 22.0, 50.0, 48.0],
+            [44.0, 54.0, 114.0, 108.0],
+            [40.0, 58.0, 110.0, 102.0],
+            [16.0, 26.0, 46.0, 42.0],
+        ]);
+        assert!(a * b == result);
+    }
+
+    #[test]
+    fn multiply_matrix_and_point() {
+        let a = Matrix4::new([
+            [1.0, 2.0, 3.0, 4.0],
+            [2.0, 4.0, 4.0, 2.0],
+            [8.0, 6.0, 4.0, 1.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ]);
+        assert!(a * point!(1, 2, 3) == point!(18, 24, 33));
+    }
+
+    #[test]
+    fn multiply_matrix_and_vector() {
+        let a = Matrix4::new([
+            [1.0, 2.0, 3.0, 4.0],
+            [2.0, 4.0, 4.0, 2.0],
+            [8.0, 6.0, 4.0, 1.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ]);
+        // The last column is ignored for a `Vector`
+        assert!(a * vector!(1, 2, 3) == vector!(14, 22, 32));
+    }
+
+    #[test]
+    fn multiply_by_identity() {
+        let a = Matrix4::new([
+            [0.0, 1.0, 2.0, 4.0],
+            [1.0, 2.0, 4.0, 8.0],
+            [2.0, 4.0, 8.0, 16.0],
+            [4.0, 8.0, 16.0, 32.0],
+        ]);
+        assert!(a * Matrix4::identity() == a);
+        assert!(Matrix4::identity() * vector!(1, 2, 3) == vector!(1, 2, 3));
+    }
+
+    #[test]
+    fn transpose_matrix() {
+        let a = Matrix4::new([
+            [0.0, 9.0, 3.0, 0.0],
+            [9.0, 8.0, 0.0, 8.0],
+            [1.0, 8.0, 5.0, 3.0],
+            [0.0, 0.0, 5.0, 8.0],
+        ]);
+        let result = Matrix4::new([
+            [0.0, 9.0, 1.0, 0.0],
+            [9.0, 8.0, 8.0, 0.0],
+            [3.0, 0.0, 5.0, 5.0],
+            [0.0, 8.0, 3.0, 8.0],
+        ]);
+        assert!(a.transpose() == result);
+        assert!(Matrix4::identity().transpose() == Matrix4::identity());
+    }
+
+    #[test]
+    fn submatrices() {
+        let a = Matrix3::new([[1.0, 5.0, 0.0], [-3.0, 2.0, 7.0], [0.0, 6.0, -3.0]]);
+        let result = Matrix2::new([[-3.0, 2.0], [0.0, 6.0]]);
+        assert!(a.submatrix(0, 2) == result);
+
+        let a = Matrix4::new([
+            [-6.0, 1.0, 1.0, 6.0],
+            [-8.0, 5.0, 8.0, 6.0],
+            [-1.0, 0.0, 8.0, 2.0],
+            [-7.0, 1.0, -1.0, 1.0],
+        ]);
+        let result = Matrix3::new([[-6.0, 1.0, 6.0], [-8.0, 8.0, 6.0], [-7.0, -1.0, 1.0]]);
+        assert!(a.submatrix(2, 1) == result);
+    }
+
+    #[test]
+    fn minors_and_cofactors() {
+        let a = Matrix3::new([[3.0, 5.0, 0.0], [2.0, -1.0, -7.0], [6.0, -1.0, 5.0]]);
+        assert_eq!(a.minor(1, 0), 25.0);
+        assert_eq!(a.minor(0, 0), -12.0);
+        assert_eq!(a.cofactor(0, 0), -12.0);
+        assert_eq!(a.cofactor(1, 0), -25.0);
+    }
+
+    #[test]
+    fn determinants() {
+        let a = Matrix3::new([[1.0, 2.0, 6.0], [-5.0, 8.0, -4.0], [2.0, 6.0, 4.0]]);
+        assert_eq!(a.cofactor(0, 0), 56.0);
+        assert_eq!(a.cofactor(0, 1), 12.0);
+        assert_eq!(a.cofactor(0, 2), -46.0);
+        assert_eq!(a.determinant(), -196.0);
+
+        let a = Matrix4::new([
+            [-2.0, -8.0, 3.0, 5.0],
+            [-3.0, 1.0, 7.0, 3.0],
+            [1.0, 2.0, -9.0, 6.0],
+            [-6.0, 7.0, 7.0, -9.0],
+        ]);
+        assert_eq!(a.cofactor(0, 0), 690.0);
+        assert_eq!(a.cofactor(0, 1), 447.0);
+        assert_eq!(a.cofactor(0, 2), 210.0);
+        assert_eq!(a.cofactor(0, 3), 51.0);
+        assert_eq!(a.determinant(), -4071.0);
+    }
+
+    #[test]
+    fn non_invertible_matrix() {
+        let a = Matrix4::new([
+            [-4.0, 2.0, -2.0, -3.0],
+            [9.0, 6.0, 2.0, 6.0],
+            [0.0, -5.0, 1.0, -5.0],
+            [0.0, 0.0, 0.0, 0.0],
+        ]);
+        assert!(!a.is_invertible());
+        assert!(a.inverse().is_none());
+    }
+
+    #[test]
+    fn nearly_singular_matrix_is_not_invertible() {
+        let a = Matrix4::new([
+            [1.0, 2.0, 3.0, 4.0],
+            [2.0, 4.0, 6.0, 8.0 + 1e-9],
+            [0.0, 1.0, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+        ]);
+        assert!(a.determinant() != 0.0);
+        assert!(!a.is_invertible());
+        assert!(a.inverse().is_none());
+    }
+
+    #[test]
+    fn inverse_matrix() {
+        let a = Matrix4::new([
+            [-5.0, 2.0, 6.0, -8.0],
+            [1.0, -5.0, 1.0, 8.0],
+            [7.0, 7.0, -6.0, -7.0],
+            [1.0, -3.0, 7.0, 4.0],
+        ]);
+        assert!(a.is_invertible());
+        let b = a.inverse().unwrap();
+        assert_eq!(a.determinant(), 532.0);
+        assert_eq!(a.cofactor(2, 3), -160.0);
+        assert!(float_eq(b[(3, 2)], -160.0 / 532.0));
+        assert_eq!(a.cofactor(3, 2), 105.0);
+        assert!(float_eq(b[(2, 3)], 105.0 / 532.0));
+        assert!(a * b == Matrix4::identity());
+    }
+
+    #[test]
+    fn multiply_product_by_inverse() {
+        let a = Matrix4::new([
+            [3.0, -9.0, 7.0, 3.0],
+            [3.0, -8.0, 2.0, -9.0],
+            [-4.0, 4.0, 4.0, 1.0],
+            [-6.0, 5.0, -1.0, 1.0],
+        ]);
+        let b = Matrix4::new([
+            [8.0, 2.0, 2.0, 2.0],
+            [3.0, -1.0, 7.0, 0.0],
+            [7.0, 0.0, 5.0, 4.0],
+            [6.0, -2.0, 0.0, 5.0],
+        ]);
+        let c = a * b;
+        assert!(c * b.inverse().unwrap() == a);
+    }
+}
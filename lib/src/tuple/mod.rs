@@ -1,9 +1,11 @@
 // Exports
+pub mod matrix;
 pub mod point;
 pub mod utils;
 pub mod vector;
 
 // Imports
+pub use matrix::Matrix4;
 pub use point::Point;
 use std::cmp::PartialEq;
 pub use vector::Vector;
@@ -18,18 +18,13 @@ impl Tuple for Point {
             x: 0.0,
             y: 0.0,
             z: 0.0,
-            w: 0.0,
+            w: 1.0,
         }
     }
 
     /// Create a new `Point` with position `(x, y, z)`
     fn new(x: f64, y: f64, z: f64) -> Self {
-        Point {
-            x: x,
-            y: y,
-            z: z,
-            w: 0.0,
-        }
+        Point { x, y, z, w: 1.0 }
     }
 }
 
@@ -42,11 +37,7 @@ impl PartialEq for Point {
         let ztrue = float_eq(self.z, other.z);
         let wtrue = float_eq(self.w, other.w);
 
-        if xtrue && ytrue && ztrue && wtrue {
-            true
-        } else {
-            false
-        }
+        xtrue && ytrue && ztrue && wtrue
     }
 }
 
@@ -139,7 +130,7 @@ mod tests {
     #[test]
     fn test_for_origin_point() {
         let p = Point::origin();
-        assert_eq!(0.0, p.w);
+        assert_eq!(1.0, p.w);
         assert_eq!(0.0, p.x);
         assert_eq!(0.0, p.y);
         assert_eq!(0.0, p.z);
@@ -151,7 +142,7 @@ mod tests {
         assert_eq!(4.0, p.x);
         assert_eq!(-4.0, p.y);
         assert_eq!(3.0, p.z);
-        assert_eq!(0.0, p.w);
+        assert_eq!(1.0, p.w);
     }
 
     #[test]
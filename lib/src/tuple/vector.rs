@@ -99,6 +99,21 @@ impl Vector {
 
         Vector::new(x_component, y_component, z_component)
     }
+
+    /// Reflect the `Vector` around the `normal`
+    ///
+    /// # Examples
+    /// ```
+    /// use ray_tracer::tuple::{Tuple, Vector};
+    /// use ray_tracer::vector;
+    ///
+    /// let v = vector!(1, -1, 0);
+    /// let n = vector!(0, 1, 0);
+    /// assert!(v.reflect(&n) == vector!(1, 1, 0));
+    /// ```
+    pub fn reflect(&self, normal: &Vector) -> Self {
+        *self - *normal * 2.0 * self.dot(normal)
+    }
 }
 
 // * Trait implementations
@@ -109,18 +124,13 @@ impl Tuple for Vector {
             x: 0.0,
             y: 0.0,
             z: 0.0,
-            w: 1.0,
+            w: 0.0,
         }
     }
 
     /// Create a new `Vector` with position `(x, y, z)`
     fn new(x: f64, y: f64, z: f64) -> Self {
-        Vector {
-            x: x,
-            y: y,
-            z: z,
-            w: 1.0,
-        }
+        Vector { x, y, z, w: 0.0 }
     }
 }
 
@@ -131,11 +141,7 @@ impl PartialEq for Vector {
         let ztrue = float_eq(self.z, other.z);
         let wtrue = float_eq(self.w, other.w);
 
-        if xtrue && ytrue && ztrue && wtrue {
-            true
-        } else {
-            false
-        }
+        xtrue && ytrue && ztrue && wtrue
     }
 }
 
@@ -230,7 +236,7 @@ mod tests {
         assert_eq!(0.0, v.x);
         assert_eq!(0.0, v.y);
         assert_eq!(0.0, v.z);
-        assert_eq!(1.0, v.w);
+        assert_eq!(0.0, v.w);
     }
 
     #[test]
@@ -239,7 +245,7 @@ mod tests {
         assert_eq!(4.0, v.x);
         assert_eq!(-4.0, v.y);
         assert_eq!(3.0, v.z);
-        assert_eq!(1.0, v.w);
+        assert_eq!(0.0, v.w);
     }
 
     #[test]
@@ -386,4 +392,19 @@ mod tests {
         let res = vector!(1, -2, 1);
         assert!(v2.cross(&v1) == res);
     }
+
+    #[test]
+    fn reflect_approaching_at_45_degrees() {
+        let v = vector!(1, -1, 0);
+        let n = vector!(0, 1, 0);
+        assert!(v.reflect(&n) == vector!(1, 1, 0));
+    }
+
+    #[test]
+    fn reflect_off_slanted_surface() {
+        let v = vector!(0, -1, 0);
+        let half = 2.0_f64.sqrt() / 2.0;
+        let n = vector!(half, half, 0);
+        assert!(v.reflect(&n) == vector!(1, 0, 0));
+    }
 }
@@ -0,0 +1,592 @@
+use crate::color; // for the macro
+use crate::color::Color; // for the type
+use crate::intersection::{Computations, Intersections};
+use crate::light::{lighting, PointLight};
+use crate::material::Material;
+use crate::point;
+use crate::ray::Ray;
+use crate::shapes::{Shape, Sphere};
+use crate::transform::scaling;
+use crate::tuple::{Point, Tuple};
+
+/// The number of times a `Ray` can bounce off reflective surfaces
+/// before the recursion stops, so that facing mirrors terminate
+pub const MAX_DEPTH: usize = 5;
+
+/// A collection of objects and the lights that illuminate them
+#[derive(Debug, Default)]
+pub struct World {
+    pub objects: Vec<Box<dyn Shape>>,
+    pub lights: Vec<PointLight>,
+}
+
+impl World {
+    /// Create an empty `World`, with no objects and no lights
+    pub fn new() -> World {
+        World {
+            objects: Vec::new(),
+            lights: Vec::new(),
+        }
+    }
+
+    /// Intersect `ray` with every object in the `World`
+    pub fn intersect_world(&self, ray: &Ray) -> Intersections<'_> {
+        let mut xs = Vec::new();
+        for object in &self.objects {
+            xs.extend(object.intersect(ray));
+        }
+
+        Intersections::new(xs)
+    }
+
+    /// Check if `point` is hidden from `light` by an object that casts
+    /// shadows
+    pub fn is_shadowed(&self, light: &PointLight, point: Point) -> bool {
+        let v = light.position - point;
+        let distance = v.magnitude();
+        let ray = Ray::new(point, v.normalize());
+
+        let xs = self.intersect_world(&ray);
+        // Only objects between `point` and the light can cast a shadow
+        xs.iter()
+            .filter(|i| i.object.casts_shadow())
+            .find(|i| i.t >= 0.0)
+            .is_some_and(|i| i.t < distance)
+    }
+
+    /// Compute the `Color` at a precomputed hit, adding up the
+    /// contribution of every light and the reflected and refracted
+    /// light
+    ///
+    /// The hit is lit from `over_point`, slightly above the surface,
+    /// so that it does not shadow itself. `remaining` is the number of
+    /// bounces still allowed. Surfaces that are both reflective and
+    /// transparent weight each term with `Computations::schlick`.
+    pub fn shade_hit(&self, comps: &Computations, remaining: usize) -> Color {
+        let surface = self
+            .lights
+            .iter()
+            .map(|light| {
+                let in_shadow = self.is_shadowed(light, comps.over_point);
+                lighting(
+                    comps.object.material(),
+                    comps.object,
+                    light,
+                    comps.over_point,
+                    comps.eyev,
+                    comps.normalv,
+                    in_shadow,
+                )
+            })
+            .fold(color!(0.0, 0.0, 0.0), |acc, c| acc + c);
+
+        let reflected = self.reflected_color(comps, remaining);
+        let refracted = self.refracted_color(comps, remaining);
+
+        let material = comps.object.material();
+        if material.reflective > 0.0 && material.transparency > 0.0 {
+            // Fresnel effect, more is reflected at grazing angles
+            let reflectance = comps.schlick();
+            surface + reflected * reflectance + refracted * (1.0 - reflectance)
+        } else {
+            surface + reflected + refracted
+        }
+    }
+
+    /// Compute the `Color` seen in the reflection at a precomputed hit,
+    /// black if the surface is not reflective or if there are no
+    /// `remaining` bounces
+    pub fn reflected_color(&self, comps: &Computations, remaining: usize) -> Color {
+        let reflective = comps.object.material().reflective;
+        if remaining == 0 || reflective == 0.0 {
+            return color!(0.0, 0.0, 0.0);
+        }
+
+        let reflect_ray = Ray::new(comps.over_point, comps.reflectv);
+        self.color_at(&reflect_ray, remaining - 1) * reflective
+    }
+
+    /// Compute the `Color` seen through a transparent surface at a
+    /// precomputed hit, black if the surface is opaque, if there are
+    /// no `remaining` bounces or in case of total internal reflection
+    pub fn refracted_color(&self, comps: &Computations, remaining: usize) -> Color {
+        let transparency = comps.object.material().transparency;
+        if remaining == 0 || transparency == 0.0 {
+            return color!(0.0, 0.0, 0.0);
+        }
+
+        // Snell's law, sin(theta_t) is found from sin(theta_i)
+        let n_ratio = comps.n1 / comps.n2;
+        let cos_i = comps.eyev.dot(&comps.normalv);
+        let sin2_t = n_ratio.powi(2) * (1.0 - cos_i.powi(2));
+        if sin2_t > 1.0 {
+            // All of the light is reflected back
+            return color!(0.0, 0.0, 0.0);
+        }
+
+        let cos_t = (1.0 - sin2_t).sqrt();
+        let direction = comps.normalv * (n_ratio * cos_i - cos_t) - comps.eyev * n_ratio;
+        let refract_ray = Ray::new(comps.under_point, direction);
+
+        self.color_at(&refract_ray, remaining - 1) * transparency
+    }
+
+    /// Compute the `Color` seen along `ray`, black if it hits nothing
+    ///
+    /// `remaining` limits the number of reflections that are followed,
+    /// `MAX_DEPTH` is a good default.
+    ///
+    /// # Examples
+    /// ```
+    /// use ray_tracer::color::Color;
+    /// use ray_tracer::ray::Ray;
+    /// use ray_tracer::tuple::{Point, Tuple, Vector};
+    /// use ray_tracer::world::{default_world, MAX_DEPTH};
+    /// use ray_tracer::{color, point, vector};
+    ///
+    /// let w = default_world();
+    /// let r = Ray::new(point!(0, 0, -5), vector!(0, 1, 0));
+    /// assert!(w.color_at(&r, MAX_DEPTH) == color!(0, 0, 0));
+    /// ```
+    pub fn color_at(&self, ray: &Ray, remaining: usize) -> Color {
+        let xs = self.intersect_world(ray);
+        match xs.hit() {
+            Some(hit) => self.shade_hit(&hit.prepare_computations(ray, &xs), remaining),
+            None => color!(0.0, 0.0, 0.0),
+        }
+    }
+}
+
+/// Create the `World` used throughout the tests: a light at
+/// `(-10, 10, -10)` and two concentric spheres centered at the origin
+pub fn default_world() -> World {
+    let light = PointLight::new(point!(-10, 10, -10), color!(1, 1, 1));
+
+    let mut outer = Sphere::new();
+    let mut material = Material::new();
+    material.color = color!(0.8, 1.0, 0.6);
+    material.diffuse = 0.7;
+    material.specular = 0.2;
+    outer.set_material(material);
+
+    let mut inner = Sphere::new();
+    inner.set_transform(scaling(0.5, 0.5, 0.5));
+
+    World {
+        objects: vec![Box::new(outer), Box::new(inner)],
+        lights: vec![light],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intersection::Intersection;
+    use crate::intersection::Intersections;
+    use crate::material::glass;
+    use crate::patterns::tests::TestPattern;
+    use crate::shapes::{Group, Plane};
+    use crate::transform::translation;
+    use crate::tuple::Vector;
+    use crate::vector;
+    use std::f64::consts::{FRAC_1_SQRT_2, FRAC_PI_2, SQRT_2};
+    use std::rc::Rc;
+
+    #[test]
+    fn create_world() {
+        let w = World::new();
+        assert!(w.objects.is_empty());
+        assert!(w.lights.is_empty());
+    }
+
+    #[test]
+    fn create_default_world() {
+        let w = default_world();
+        assert_eq!(w.lights.len(), 1);
+        assert!(w.lights[0] == PointLight::new(point!(-10, 10, -10), color!(1, 1, 1)));
+        assert_eq!(w.objects.len(), 2);
+        assert_eq!(w.objects[0].material().color, color!(0.8, 1.0, 0.6));
+        assert!(*w.objects[1].transform() == scaling(0.5, 0.5, 0.5));
+    }
+
+    #[test]
+    fn intersect_default_world() {
+        let w = default_world();
+        let r = Ray::new(point!(0, 0, -5), vector!(0, 0, 1));
+        let xs = w.intersect_world(&r);
+        assert_eq!(xs.len(), 4);
+        assert_eq!(xs[0].t, 4.0);
+        assert_eq!(xs[1].t, 4.5);
+        assert_eq!(xs[2].t, 5.5);
+        assert_eq!(xs[3].t, 6.0);
+    }
+
+    #[test]
+    fn shade_intersection() {
+        let w = default_world();
+        let r = Ray::new(point!(0, 0, -5), vector!(0, 0, 1));
+        let i = Intersection::new(4.0, w.objects[0].as_ref());
+        let comps = i.prepare_computations(&r, &[i]);
+        let c = w.shade_hit(&comps, MAX_DEPTH);
+        assert!((c.red - 0.38066).abs() < 1e-5);
+        assert!((c.green - 0.47583).abs() < 1e-5);
+        assert!((c.blue - 0.2855).abs() < 1e-5);
+    }
+
+    #[test]
+    fn shade_intersection_from_inside() {
+        let mut w = default_world();
+        w.lights = vec![PointLight::new(point!(0, 0.25, 0), color!(1, 1, 1))];
+        let r = Ray::new(point!(0, 0, 0), vector!(0, 0, 1));
+        let i = Intersection::new(0.5, w.objects[1].as_ref());
+        let comps = i.prepare_computations(&r, &[i]);
+        let c = w.shade_hit(&comps, MAX_DEPTH);
+        assert!((c.red - 0.90498).abs() < 1e-5);
+        assert!((c.green - 0.90498).abs() < 1e-5);
+        assert!((c.blue - 0.90498).abs() < 1e-5);
+    }
+
+    #[test]
+    fn color_when_ray_misses() {
+        let w = default_world();
+        let r = Ray::new(point!(0, 0, -5), vector!(0, 1, 0));
+        assert!(w.color_at(&r, MAX_DEPTH) == color!(0, 0, 0));
+    }
+
+    #[test]
+    fn color_when_ray_hits() {
+        let w = default_world();
+        let r = Ray::new(point!(0, 0, -5), vector!(0, 0, 1));
+        let c = w.color_at(&r, MAX_DEPTH);
+        assert!((c.red - 0.38066).abs() < 1e-5);
+        assert!((c.green - 0.47583).abs() < 1e-5);
+        assert!((c.blue - 0.2855).abs() < 1e-5);
+    }
+
+    #[test]
+    fn color_with_intersection_behind_ray() {
+        let mut w = default_world();
+        for object in w.objects.iter_mut() {
+            let mut material = object.material().clone();
+            material.ambient = 1.0;
+            object.set_material(material);
+        }
+        let r = Ray::new(point!(0, 0, 0.75), vector!(0, 0, -1));
+        // The inner sphere is hit, and only its ambient color is seen
+        let inner_color = w.objects[1].material().color;
+        assert!(w.color_at(&r, MAX_DEPTH) == inner_color);
+    }
+
+    #[test]
+    fn multiple_lights_add_up() {
+        let mut w = default_world();
+        let r = Ray::new(point!(0, 0, -5), vector!(0, 0, 1));
+        let single = w.color_at(&r, MAX_DEPTH);
+        w.lights.push(w.lights[0]);
+        assert!(w.color_at(&r, MAX_DEPTH) == single * 2.0);
+    }
+
+    #[test]
+    fn no_shadow_when_nothing_is_collinear() {
+        let w = default_world();
+        assert!(!w.is_shadowed(&w.lights[0], point!(0, 10, 0)));
+    }
+
+    #[test]
+    fn shadow_when_object_between_point_and_light() {
+        let w = default_world();
+        assert!(w.is_shadowed(&w.lights[0], point!(10, -10, 10)));
+    }
+
+    #[test]
+    fn no_shadow_when_object_behind_light() {
+        let w = default_world();
+        assert!(!w.is_shadowed(&w.lights[0], point!(-20, 20, -20)));
+    }
+
+    #[test]
+    fn no_shadow_when_object_behind_point() {
+        let w = default_world();
+        assert!(!w.is_shadowed(&w.lights[0], point!(-2, 2, -2)));
+    }
+
+    #[test]
+    fn no_shadow_from_objects_that_opt_out() {
+        let mut w = default_world();
+        for object in w.objects.iter_mut() {
+            object.set_casts_shadow(false);
+        }
+        assert!(!w.is_shadowed(&w.lights[0], point!(10, -10, 10)));
+    }
+
+    #[test]
+    fn no_shadow_from_group_that_opts_out() {
+        let mut w = default_world();
+        let mut g = Group::new();
+        for object in w.objects.drain(..) {
+            g.add_child(object);
+        }
+        g.set_casts_shadow(false);
+        w.objects.push(Box::new(g));
+        assert!(!w.is_shadowed(&w.lights[0], point!(10, -10, 10)));
+    }
+
+    #[test]
+    fn shade_hit_in_shadow() {
+        let mut w = World::new();
+        w.lights
+            .push(PointLight::new(point!(0, 0, -10), color!(1, 1, 1)));
+        w.objects.push(Box::new(Sphere::new()));
+        let mut s2 = Sphere::new();
+        s2.set_transform(translation(0.0, 0.0, 10.0));
+        w.objects.push(Box::new(s2));
+
+        let r = Ray::new(point!(0, 0, 5), vector!(0, 0, 1));
+        let i = Intersection::new(4.0, w.objects[1].as_ref());
+        let comps = i.prepare_computations(&r, &[i]);
+        assert!(w.shade_hit(&comps, MAX_DEPTH) == color!(0.1, 0.1, 0.1));
+    }
+
+    /// The default world with a reflective plane below the spheres
+    fn world_with_mirror_floor(reflective: f64) -> World {
+        let mut w = default_world();
+        let mut floor = Plane::new();
+        let mut material = Material::new();
+        material.reflective = reflective;
+        floor.set_material(material);
+        floor.set_transform(translation(0.0, -1.0, 0.0));
+        w.objects.push(Box::new(floor));
+        w
+    }
+
+    #[test]
+    fn reflected_color_of_nonreflective_material() {
+        let mut w = default_world();
+        let mut material = w.objects[1].material().clone();
+        material.ambient = 1.0;
+        w.objects[1].set_material(material);
+        let r = Ray::new(point!(0, 0, 0), vector!(0, 0, 1));
+        let i = Intersection::new(1.0, w.objects[1].as_ref());
+        let comps = i.prepare_computations(&r, &[i]);
+        assert!(w.reflected_color(&comps, MAX_DEPTH) == color!(0, 0, 0));
+    }
+
+    #[test]
+    fn reflected_color_of_reflective_material() {
+        let w = world_with_mirror_floor(0.5);
+        let r = Ray::new(point!(0, 0, -3), vector!(0, -FRAC_1_SQRT_2, FRAC_1_SQRT_2));
+        let i = Intersection::new(SQRT_2, w.objects[2].as_ref());
+        let comps = i.prepare_computations(&r, &[i]);
+        let c = w.reflected_color(&comps, MAX_DEPTH);
+        assert!((c.red - 0.19032).abs() < 1e-4);
+        assert!((c.green - 0.2379).abs() < 1e-4);
+        assert!((c.blue - 0.14274).abs() < 1e-4);
+    }
+
+    #[test]
+    fn shade_hit_with_reflective_material() {
+        let w = world_with_mirror_floor(0.5);
+        let r = Ray::new(point!(0, 0, -3), vector!(0, -FRAC_1_SQRT_2, FRAC_1_SQRT_2));
+        let i = Intersection::new(SQRT_2, w.objects[2].as_ref());
+        let comps = i.prepare_computations(&r, &[i]);
+        let c = w.shade_hit(&comps, MAX_DEPTH);
+        assert!((c.red - 0.87677).abs() < 1e-4);
+        assert!((c.green - 0.92436).abs() < 1e-4);
+        assert!((c.blue - 0.82918).abs() < 1e-4);
+    }
+
+    #[test]
+    fn reflected_color_at_maximum_depth() {
+        let w = world_with_mirror_floor(0.5);
+        let r = Ray::new(point!(0, 0, -3), vector!(0, -FRAC_1_SQRT_2, FRAC_1_SQRT_2));
+        let i = Intersection::new(SQRT_2, w.objects[2].as_ref());
+        let comps = i.prepare_computations(&r, &[i]);
+        assert!(w.reflected_color(&comps, 0) == color!(0, 0, 0));
+    }
+
+    #[test]
+    fn color_at_with_mutually_reflective_surfaces() {
+        let mut w = World::new();
+        w.lights
+            .push(PointLight::new(point!(0, 0, 0), color!(1, 1, 1)));
+        let mut material = Material::new();
+        material.reflective = 1.0;
+        let mut lower = Plane::new();
+        lower.set_material(material.clone());
+        lower.set_transform(translation(0.0, -1.0, 0.0));
+        let mut upper = Plane::new();
+        upper.set_material(material);
+        upper.set_transform(translation(0.0, 1.0, 0.0));
+        w.objects.push(Box::new(lower));
+        w.objects.push(Box::new(upper));
+
+        // The ray bounces between the planes until the depth runs out
+        let r = Ray::new(point!(0, 0, 0), vector!(0, 1, 0));
+        // Every one of the six hits adds ambient, diffuse and specular
+        // light, the light being at a distance of one from each
+        let c = w.color_at(&r, MAX_DEPTH);
+        assert!(c == color!(11.4, 11.4, 11.4));
+    }
+
+    #[test]
+    fn refracted_color_of_opaque_surface() {
+        let w = default_world();
+        let shape = w.objects[0].as_ref();
+        let r = Ray::new(point!(0, 0, -5), vector!(0, 0, 1));
+        let xs = [Intersection::new(4.0, shape), Intersection::new(6.0, shape)];
+        let comps = xs[0].prepare_computations(&r, &xs);
+        assert!(w.refracted_color(&comps, MAX_DEPTH) == color!(0, 0, 0));
+    }
+
+    #[test]
+    fn refracted_color_at_maximum_depth() {
+        let mut w = default_world();
+        let mut material = w.objects[0].material().clone();
+        material.transparency = 1.0;
+        material.refractive_index = 1.5;
+        w.objects[0].set_material(material);
+        let shape = w.objects[0].as_ref();
+        let r = Ray::new(point!(0, 0, -5), vector!(0, 0, 1));
+        let xs = [Intersection::new(4.0, shape), Intersection::new(6.0, shape)];
+        let comps = xs[0].prepare_computations(&r, &xs);
+        assert!(w.refracted_color(&comps, 0) == color!(0, 0, 0));
+    }
+
+    #[test]
+    fn refracted_color_under_total_internal_reflection() {
+        let mut w = default_world();
+        let mut material = w.objects[0].material().clone();
+        material.transparency = 1.0;
+        material.refractive_index = 1.5;
+        w.objects[0].set_material(material);
+        let shape = w.objects[0].as_ref();
+        let r = Ray::new(point!(0, 0, FRAC_1_SQRT_2), vector!(0, 1, 0));
+        let xs = [
+            Intersection::new(-FRAC_1_SQRT_2, shape),
+            Intersection::new(FRAC_1_SQRT_2, shape),
+        ];
+        // The ray is inside the sphere, so look at the second hit
+        let comps = xs[1].prepare_computations(&r, &xs);
+        assert!(w.refracted_color(&comps, MAX_DEPTH) == color!(0, 0, 0));
+    }
+
+    #[test]
+    fn refracted_color_with_refracted_ray() {
+        let mut w = default_world();
+        let mut material = w.objects[0].material().clone();
+        material.ambient = 1.0;
+        material.pattern = Some(Rc::new(TestPattern::default()));
+        w.objects[0].set_material(material);
+        let mut material = w.objects[1].material().clone();
+        material.transparency = 1.0;
+        material.refractive_index = 1.5;
+        w.objects[1].set_material(material);
+        // The refracted ray hits the outer sphere from the inside, where
+        // it is in its own shadow and only the ambient color is left
+        // showing the point that was hit
+        let (a, b) = (w.objects[0].as_ref(), w.objects[1].as_ref());
+        let r = Ray::new(point!(0, 0, 0.1), vector!(0, 1, 0));
+        let xs = Intersections::new(vec![
+            Intersection::new(-0.9899, a),
+            Intersection::new(-0.4899, b),
+            Intersection::new(0.4899, b),
+            Intersection::new(0.9899, a),
+        ]);
+        let comps = xs[2].prepare_computations(&r, &xs);
+        let c = w.refracted_color(&comps, MAX_DEPTH);
+        assert!(c.red.abs() < 1e-4);
+        assert!((c.green - 0.99888).abs() < 1e-4);
+        assert!((c.blue - 0.04725).abs() < 1e-4);
+    }
+
+    #[test]
+    fn shade_hit_with_transparent_material() {
+        let mut w = default_world();
+        let mut floor = Plane::new();
+        floor.set_transform(translation(0.0, -1.0, 0.0));
+        floor.set_material(Material {
+            transparency: 0.5,
+            refractive_index: 1.5,
+            ..Material::new()
+        });
+        let mut ball = Sphere::new();
+        ball.set_transform(translation(0.0, -3.5, -0.5));
+        ball.set_material(Material {
+            color: color!(1, 0, 0),
+            ambient: 0.5,
+            ..Material::new()
+        });
+        w.objects.push(Box::new(floor));
+        w.objects.push(Box::new(ball));
+
+        let r = Ray::new(point!(0, 0, -3), vector!(0, -FRAC_1_SQRT_2, FRAC_1_SQRT_2));
+        let xs = [Intersection::new(SQRT_2, w.objects[2].as_ref())];
+        let comps = xs[0].prepare_computations(&r, &xs);
+        let c = w.shade_hit(&comps, MAX_DEPTH);
+        assert!((c.red - 0.93642).abs() < 1e-4);
+        assert!((c.green - 0.68642).abs() < 1e-4);
+        assert!((c.blue - 0.68642).abs() < 1e-4);
+    }
+
+    #[test]
+    fn glass_sphere_lets_the_background_through() {
+        let mut w = World::new();
+        w.lights
+            .push(PointLight::new(point!(0, 10, -10), color!(1, 1, 1)));
+        let mut wall = Plane::new();
+        wall.set_transform(translation(0.0, 0.0, 5.0) * crate::transform::rotation_x(FRAC_PI_2));
+        w.objects.push(Box::new(wall));
+        let r = Ray::new(point!(0, 0, -5), vector!(0, 0, 1));
+        let background = w.color_at(&r, MAX_DEPTH);
+
+        // A ball with no surface color of its own, that the ray goes
+        // straight through since it hits the center
+        let invisible = Material {
+            ambient: 0.0,
+            diffuse: 0.0,
+            specular: 0.0,
+            ..glass()
+        };
+        let mut ball = Sphere::new();
+        ball.set_material(invisible.clone());
+        ball.set_casts_shadow(false);
+        w.objects.push(Box::new(ball));
+        assert!(w.color_at(&r, MAX_DEPTH) == background);
+
+        w.objects[1].set_material(Material {
+            transparency: 0.0,
+            ..invisible
+        });
+        assert!(w.color_at(&r, MAX_DEPTH) == color!(0, 0, 0));
+    }
+
+    #[test]
+    fn shade_hit_with_reflective_transparent_material() {
+        let mut w = default_world();
+        let mut floor = Plane::new();
+        floor.set_transform(translation(0.0, -1.0, 0.0));
+        floor.set_material(Material {
+            reflective: 0.5,
+            transparency: 0.5,
+            refractive_index: 1.5,
+            ..Material::new()
+        });
+        let mut ball = Sphere::new();
+        ball.set_transform(translation(0.0, -3.5, -0.5));
+        ball.set_material(Material {
+            color: color!(1, 0, 0),
+            ambient: 0.5,
+            ..Material::new()
+        });
+        w.objects.push(Box::new(floor));
+        w.objects.push(Box::new(ball));
+
+        let r = Ray::new(point!(0, 0, -3), vector!(0, -FRAC_1_SQRT_2, FRAC_1_SQRT_2));
+        let xs = [Intersection::new(SQRT_2, w.objects[2].as_ref())];
+        let comps = xs[0].prepare_computations(&r, &xs);
+        let c = w.shade_hit(&comps, MAX_DEPTH);
+        assert!((c.red - 0.93391).abs() < 1e-4);
+        assert!((c.green - 0.69643).abs() < 1e-4);
+        assert!((c.blue - 0.69243).abs() < 1e-4);
+    }
+}